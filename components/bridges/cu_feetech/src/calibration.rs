@@ -4,16 +4,24 @@
 //! `(min + max) / 2` and is used as the zero reference when converting
 //! to degrees or radians.
 //!
-//! Run the `feetech-calibrate` binary to generate a `calibration.json`.
+//! Run the `feetech-calibrate` binary to generate a `calibration.json`, or
+//! let the bridge refine it while running with [`AutoCalibration`].
+//!
+//! [`ConversionConfig`] bundles that calibration with the unit settings into
+//! one file, so an arm setup can be copied to another machine as a whole.
 
+use cu29::clock::{CuDuration, CuTime};
 use cu29::units::si::angle::{degree, radian};
 use cu29::units::si::f32::Angle;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::mpsc;
+use std::thread::JoinHandle;
 
 /// Output unit for published positions.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Units {
     /// Raw 16-bit register values (0–65535).  No calibration needed.
     #[default]
@@ -23,6 +31,7 @@ pub enum Units {
     /// Radians relative to the calibration center (0 = center).
     Rad,
     /// Normalized range [-1, 1]: min → -1, center → 0, max → 1. Same scale for leader/follower.
+    #[serde(alias = "norm")]
     Normalize,
 }
 
@@ -49,6 +58,12 @@ impl FromStr for Units {
 /// Actual value is model-dependent; set via bridge config `ticks_per_rev`.
 pub const DEFAULT_TICKS_PER_REV: u32 = 4096;
 
+/// `true` when these output / input / velocity units need a servo
+/// calibration.  Velocities only need it for the normalized scale.
+pub fn needs_calibration(output: Units, input: Units, velocity: Units) -> bool {
+    output != Units::Raw || input != Units::Raw || velocity == Units::Normalize
+}
+
 impl Units {
     /// Convert a raw 16-bit tick to the output unit.
     ///
@@ -97,6 +112,106 @@ impl Units {
         };
         raw.round().clamp(0.0, 65535.0) as u16
     }
+
+    /// Convert a raw speed (ticks per second) to this unit per second.
+    ///
+    /// Same scale as [`from_raw`](Self::from_raw) without the center offset,
+    /// so for `Normalize` a servo sweeping its whole calibrated range in one
+    /// second reads `2.0`.  `param` is as for `from_raw`.
+    #[inline]
+    pub fn rate_from_raw(self, ticks_per_s: f32, param: f32) -> f32 {
+        match self {
+            Self::Raw => ticks_per_s,
+            Self::Deg => ticks_per_s * 360.0 / param,
+            Self::Rad => ticks_per_s * core::f32::consts::TAU / param,
+            Self::Normalize => {
+                if param <= 0.0 {
+                    0.0
+                } else {
+                    ticks_per_s / param
+                }
+            }
+        }
+    }
+
+    /// One full revolution in this unit, or `None` for units that are not angles.
+    #[inline]
+    pub fn full_turn(self) -> Option<f32> {
+        match self {
+            Self::Deg => Some(360.0),
+            Self::Rad => Some(core::f32::consts::TAU),
+            Self::Raw | Self::Normalize => None,
+        }
+    }
+
+    /// Wrap an angle into `[-180, 180)` degrees / `[-π, π)` radians.
+    ///
+    /// Values in `Raw` or `Normalize` are returned unchanged.
+    #[inline]
+    pub fn wrap(self, value: f32) -> f32 {
+        match self.full_turn() {
+            Some(turn) => {
+                let half = turn / 2.0;
+                (value + half).rem_euclid(turn) - half
+            }
+            None => value,
+        }
+    }
+
+    /// Undo [`wrap`](Self::wrap): pick the angle equivalent to `value`
+    /// (modulo one turn) that is closest to `reference`.
+    ///
+    /// `reference` is an unwrapped angle, typically the last known present
+    /// position.  A wrapped command is therefore interpreted as "the nearest
+    /// way to get there", which resolves the ambiguity for servos with more
+    /// than 360° of travel or a center far from the middle of the range.
+    #[inline]
+    pub fn unwrap_near(self, value: f32, reference: f32) -> f32 {
+        match self.full_turn() {
+            Some(_) => reference + self.wrap(value - reference),
+            None => value,
+        }
+    }
+
+    /// Quantization error of commanding `value`: the requested value minus the
+    /// value that the rounded raw tick actually represents.
+    ///
+    /// For `Deg`/`Rad` the magnitude is at most half a tick (e.g. ~0.044° at
+    /// 4096 ticks/rev) unless `value` falls outside the 16-bit register range.
+    #[inline]
+    pub fn quantization_error(self, value: f32, center: f32, param: f32) -> f32 {
+        let raw = self.to_raw(value, center, param);
+        value - self.from_raw(raw, center, param)
+    }
+}
+
+/// Accumulated quantization error for one servo, in the goal unit.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct QuantizationStats {
+    /// Largest absolute error seen so far.
+    pub max_abs: f32,
+    /// Sum of absolute errors, for the mean.
+    pub sum_abs: f32,
+    /// Number of commanded values recorded.
+    pub count: u32,
+}
+
+impl QuantizationStats {
+    pub fn record(&mut self, error: f32) {
+        let abs = error.abs();
+        self.max_abs = self.max_abs.max(abs);
+        self.sum_abs += abs;
+        self.count = self.count.saturating_add(1);
+    }
+
+    /// Mean absolute error, or 0 when nothing was recorded.
+    pub fn mean_abs(&self) -> f32 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.sum_abs / self.count as f32
+        }
+    }
 }
 
 // =========================================================================
@@ -104,7 +219,7 @@ impl Units {
 // =========================================================================
 
 /// Calibration for a single servo.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ServoCalibration {
     pub id: u8,
     pub min: u16,
@@ -124,7 +239,7 @@ impl ServoCalibration {
 }
 
 /// Calibration data for all servos on a bus.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub struct CalibrationData {
     pub servos: Vec<ServoCalibration>,
 }
@@ -136,9 +251,38 @@ impl CalibrationData {
             .map_err(|e| std::io::Error::other(format!("bad calibration JSON: {e}")))
     }
 
+    /// Write the data as JSON.
+    ///
+    /// The file is written next to `path` under a temporary name and then
+    /// renamed over it, so a crash mid-write never leaves a truncated file.
     pub fn save(&self, path: &Path) -> std::io::Result<()> {
-        let json = serde_json::to_string_pretty(self)?;
-        std::fs::write(path, json)
+        write_atomic(path, &serde_json::to_string_pretty(self)?)
+    }
+
+    /// Widen the recorded range of servo `id` to include `raw`, adding an
+    /// entry if there is none.  Returns `true` if anything changed.
+    pub fn widen(&mut self, id: u8, raw: u16) -> bool {
+        match self.servos.iter_mut().find(|s| s.id == id) {
+            Some(s) if raw < s.min => s.min = raw,
+            Some(s) if raw > s.max => s.max = raw,
+            Some(_) => return false,
+            None => self.servos.push(ServoCalibration {
+                id,
+                min: raw,
+                max: raw,
+            }),
+        }
+        true
+    }
+
+    /// Of two reads outside servo `id`'s range, the one that widens it less.
+    fn least_extreme(&self, id: u8, a: u16, b: u16) -> u16 {
+        match self.servos.iter().find(|s| s.id == id) {
+            Some(s) if a > s.max && b > s.max => a.min(b),
+            Some(s) if a < s.min && b < s.min => a.max(b),
+            // Unknown servo or reads on both sides: keep the latest.
+            _ => b,
+        }
     }
 
     /// Look up the center (midpoint) for a servo by bus ID.
@@ -157,3 +301,316 @@ impl CalibrationData {
             .map(|s| (s.max as f32 - s.min as f32) / 2.0)
     }
 }
+
+/// Write `contents` to a temporary file next to `path`, then rename it over `path`.
+fn write_atomic(path: &Path, contents: &str) -> std::io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    {
+        let mut file = std::fs::File::create(&tmp)?;
+        std::io::Write::write_all(&mut file, contents.as_bytes())?;
+        file.sync_all()?;
+    }
+    std::fs::rename(&tmp, path)
+}
+
+// =========================================================================
+// Portable conversion setup
+// =========================================================================
+
+/// Version written by [`ConversionConfig`]; files with another version are refused.
+pub const CONVERSION_FORMAT_VERSION: u32 = 1;
+
+/// Everything that decides how raw ticks map to published and commanded
+/// values: units, ticks per revolution, angle wrapping and the per-servo
+/// calibration.
+///
+/// Written as JSON with one named field per setting, e.g.
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "output_units": "deg",
+///   "input_units": "deg",
+///   "velocity_units": "deg",
+///   "ticks_per_rev": 4096,
+///   "wrap_angles": false,
+///   "servos": [{ "id": 1, "min": 1000, "max": 3000 }]
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConversionConfig {
+    pub version: u32,
+    pub output_units: Units,
+    pub input_units: Units,
+    pub velocity_units: Units,
+    pub ticks_per_rev: u32,
+    pub wrap_angles: bool,
+    /// Calibration of the exported servos, in bus order.
+    pub servos: Vec<ServoCalibration>,
+}
+
+impl ConversionConfig {
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        serde_json::from_str(&contents)
+            .map_err(|e| std::io::Error::other(format!("bad conversion JSON: {e}")))
+    }
+
+    /// Write the setup as JSON, atomically like [`CalibrationData::save`].
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        write_atomic(path, &serde_json::to_string_pretty(self)?)
+    }
+
+    /// Check that this setup can drive servos `ids`.
+    ///
+    /// Every servo needs a calibration entry once a unit is not `Raw`, and a
+    /// non-empty range once a unit is `Normalize`.
+    pub fn validate(&self, ids: &[u8]) -> Result<(), String> {
+        if self.version != CONVERSION_FORMAT_VERSION {
+            return Err(format!(
+                "unsupported version {} (expected {CONVERSION_FORMAT_VERSION})",
+                self.version
+            ));
+        }
+        if self.ticks_per_rev == 0 {
+            return Err("ticks_per_rev must be positive".to_string());
+        }
+        if self.wrap_angles
+            && self.output_units.full_turn().is_none()
+            && self.input_units.full_turn().is_none()
+        {
+            return Err("wrap_angles needs deg or rad output or input units".to_string());
+        }
+        for (n, s) in self.servos.iter().enumerate() {
+            if s.min > s.max {
+                return Err(format!("servo {}: min {} > max {}", s.id, s.min, s.max));
+            }
+            if self.servos[..n].iter().any(|o| o.id == s.id) {
+                return Err(format!("servo {} listed twice", s.id));
+            }
+        }
+        let units = [self.output_units, self.input_units, self.velocity_units];
+        let calibrated = self.output_units != Units::Raw || self.input_units != Units::Raw;
+        let normalized = units.contains(&Units::Normalize);
+        for &id in ids {
+            match self.servos.iter().find(|s| s.id == id) {
+                None if calibrated || normalized => {
+                    return Err(format!("no calibration entry for servo {id}"));
+                }
+                Some(s) if normalized && s.range() == 0 => {
+                    return Err(format!("servo {id} has an empty range (normalize)"));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+// =========================================================================
+// Auto-calibration
+// =========================================================================
+
+/// Consecutive agreeing reads needed before a range is widened.
+pub const AUTO_CAL_SAMPLES: usize = 3;
+
+/// Maximum spread, in raw ticks, between reads that count as agreeing.
+pub const AUTO_CAL_AGREEMENT: u16 = 16;
+
+/// Calibration refined from live readings and saved back to disk.
+///
+/// A reading outside a servo's recorded range widens it only once
+/// [`AUTO_CAL_SAMPLES`] consecutive reads of that servo agree within
+/// [`AUTO_CAL_AGREEMENT`] ticks, and then only up to the least extreme of
+/// them, so a single corrupt read cannot stretch the range.  Periodic saves
+/// are handed to a writer thread so the file I/O stays out of the cycle; the
+/// final save on shutdown is synchronous.  Conversions keep using the ranges
+/// loaded at startup; widened ranges take effect on the next start.
+#[derive(Debug)]
+pub struct AutoCalibration {
+    data: CalibrationData,
+    path: PathBuf,
+    interval: Option<CuDuration>,
+    last_save: Option<CuTime>,
+    dirty: bool,
+    candidates: Vec<WidenCandidate>,
+    writer: Option<CalibrationWriter>,
+}
+
+/// Reads of one servo that fell outside its range, not yet trusted.
+#[derive(Debug, Clone, Copy)]
+struct WidenCandidate {
+    id: u8,
+    /// Least extreme of the agreeing reads.
+    raw: u16,
+    /// Any read of the run, to check agreement against.
+    last: u16,
+    count: usize,
+}
+
+/// Background thread writing calibration snapshots to disk.
+#[derive(Debug)]
+struct CalibrationWriter {
+    tx: mpsc::Sender<CalibrationData>,
+    errors: mpsc::Receiver<std::io::Error>,
+    handle: JoinHandle<()>,
+}
+
+impl CalibrationWriter {
+    fn spawn(path: PathBuf) -> std::io::Result<Self> {
+        let (tx, rx) = mpsc::channel::<CalibrationData>();
+        let (err_tx, errors) = mpsc::channel();
+        let handle = std::thread::Builder::new()
+            .name("feetech-calibration".into())
+            .spawn(move || {
+                for data in rx {
+                    if let Err(e) = data.save(&path) {
+                        let _ = err_tx.send(e);
+                    }
+                }
+            })?;
+        Ok(Self { tx, errors, handle })
+    }
+}
+
+impl AutoCalibration {
+    pub fn new(data: CalibrationData, path: PathBuf, interval: Option<CuDuration>) -> Self {
+        Self {
+            data,
+            path,
+            interval,
+            last_save: None,
+            dirty: false,
+            candidates: Vec::new(),
+            writer: None,
+        }
+    }
+
+    /// Current calibration, including ranges widened since startup.
+    pub fn data(&self) -> &CalibrationData {
+        &self.data
+    }
+
+    /// `true` when there are widened ranges not yet saved.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Record a present position read from servo `id`.
+    pub fn observe(&mut self, id: u8, raw: u16) {
+        let inside = self
+            .data
+            .servos
+            .iter()
+            .any(|s| s.id == id && (s.min..=s.max).contains(&raw));
+        let slot = self.candidates.iter().position(|c| c.id == id);
+        if inside {
+            if let Some(slot) = slot {
+                self.candidates.swap_remove(slot);
+            }
+            return;
+        }
+        let candidate = match slot {
+            Some(slot) if self.candidates[slot].last.abs_diff(raw) <= AUTO_CAL_AGREEMENT => {
+                let c = &mut self.candidates[slot];
+                c.count += 1;
+                c.last = raw;
+                c.raw = self.data.least_extreme(id, c.raw, raw);
+                *c
+            }
+            _ => {
+                let fresh = WidenCandidate {
+                    id,
+                    raw,
+                    last: raw,
+                    count: 1,
+                };
+                match slot {
+                    Some(slot) => self.candidates[slot] = fresh,
+                    None => self.candidates.push(fresh),
+                }
+                fresh
+            }
+        };
+        if candidate.count >= AUTO_CAL_SAMPLES {
+            self.candidates.retain(|c| c.id != id);
+            if self.data.widen(id, candidate.raw) {
+                self.dirty = true;
+            }
+        }
+    }
+
+    /// Queue a save on the writer thread if the data changed and the
+    /// interval since the last save elapsed.
+    ///
+    /// Returns `true` if a save was queued.  A failure of an earlier queued
+    /// save is returned here, and its data is saved again next time.
+    pub fn save_if_due(&mut self, now: CuTime) -> std::io::Result<bool> {
+        let Some(interval) = self.interval else {
+            return Ok(false);
+        };
+        if let Some(writer) = &self.writer
+            && let Ok(e) = writer.errors.try_recv()
+        {
+            self.dirty = true;
+            return Err(e);
+        }
+        let last = *self.last_save.get_or_insert(now);
+        if now - last < interval {
+            return Ok(false);
+        }
+        self.last_save = Some(now);
+        if !self.dirty {
+            return Ok(false);
+        }
+        if self.writer.is_none() {
+            self.writer = Some(CalibrationWriter::spawn(self.path.clone())?);
+        }
+        let writer = self.writer.as_ref().expect("writer spawned above");
+        writer
+            .tx
+            .send(self.data.clone())
+            .map_err(|_| std::io::Error::other("calibration writer thread exited"))?;
+        self.dirty = false;
+        Ok(true)
+    }
+
+    /// Wait for queued saves to finish and stop the writer thread.
+    ///
+    /// Returns the first error of a queued save; its data is marked unsaved.
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        let Some(writer) = self.writer.take() else {
+            return Ok(());
+        };
+        drop(writer.tx);
+        if writer.handle.join().is_err() {
+            self.dirty = true;
+            return Err(std::io::Error::other("calibration writer thread panicked"));
+        }
+        match writer.errors.try_recv() {
+            Ok(e) => {
+                self.dirty = true;
+                Err(e)
+            }
+            Err(_) => Ok(()),
+        }
+    }
+
+    /// Save synchronously if the data changed since the last save, after
+    /// waiting for queued saves.  Meant for shutdown.
+    ///
+    /// Returns `true` if the file was written.
+    pub fn save_if_changed(&mut self, now: CuTime) -> std::io::Result<bool> {
+        // A failed queued save is retried by the write below.
+        let _ = self.flush();
+        self.last_save = Some(now);
+        if !self.dirty {
+            return Ok(false);
+        }
+        self.data.save(&self.path)?;
+        self.dirty = false;
+        Ok(true)
+    }
+}
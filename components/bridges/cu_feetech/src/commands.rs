@@ -0,0 +1,128 @@
+//! Per-cycle resolution of incoming commands.
+//!
+//! Commands arriving on the Tx channels during a cycle are not applied as
+//! they arrive.  They are collected in [`PendingCommands`] and resolved once
+//! in `postprocess`, so the outcome does not depend on the order the
+//! runtime delivers them in.  Precedence, highest first:
+//!
+//! 1. **E-stop engage** (`estop` with `engaged: true`): torque is cut on
+//!    every servo and the e-stop latches.  Any goal in the same cycle is
+//!    dropped.  Only the transition acts: engage messages that keep arriving
+//!    while latched change nothing.
+//! 2. **E-stop release** (`estop` with `engaged: false`): the latch clears
+//!    and servos hold where they are.  Goals in the same cycle are still
+//!    dropped, so motion only resumes on a goal sent after the release.
+//! 3. **Goal** (`goal_positions`, a group write, or the hold issued for a
+//!    stale command): written to the servos, unless the e-stop is latched.
+//!    Only the last goal queued in a cycle is applied.
+//!
+//! The pending set is part of the bridge's frozen state: a snapshot can be
+//! taken between `send` and `postprocess`, and a command received in that
+//! window must not be lost on replay.
+
+use crate::messages::{JointPositions, MAX_SERVOS};
+use bincode::de::Decoder;
+use bincode::enc::Encoder;
+use bincode::error::{DecodeError, EncodeError};
+use bincode::{Decode, Encode};
+use heapless::Vec as HeaplessVec;
+
+/// Goal position command for one cycle.
+#[derive(Debug, Clone)]
+pub enum GoalCommand {
+    /// Move to these positions.
+    Positions(JointPositions),
+    /// Move only the servos in `slots` (bridge slot indices) to the matching
+    /// entries of `positions`; the others keep their last goal.
+    Group {
+        slots: HeaplessVec<u8, MAX_SERVOS>,
+        positions: JointPositions,
+    },
+    /// Hold the present positions (refused stale command).
+    HoldPresent,
+}
+
+/// Command chosen for a cycle.
+#[derive(Debug, Clone)]
+pub enum ResolvedCommand {
+    /// Nothing to do.
+    Idle,
+    /// Cut torque on every servo.
+    EStop,
+    /// Leave the e-stop: hold present positions and restore torque.
+    Release,
+    /// Apply a goal.
+    Goal(GoalCommand),
+}
+
+/// Commands received during the current cycle.
+#[derive(Debug, Clone, Default)]
+pub struct PendingCommands {
+    /// Last e-stop state received this cycle.
+    pub estop: Option<bool>,
+    /// Last goal received this cycle.
+    pub goal: Option<GoalCommand>,
+}
+
+impl PendingCommands {
+    /// Pick the command that wins this cycle and clear the pending set.
+    ///
+    /// `estopped` is the latched e-stop state; it is updated in place.
+    pub fn resolve(&mut self, estopped: &mut bool) -> ResolvedCommand {
+        let PendingCommands { estop, goal } = core::mem::take(self);
+        match estop {
+            Some(true) if !*estopped => {
+                *estopped = true;
+                ResolvedCommand::EStop
+            }
+            Some(false) if *estopped => {
+                *estopped = false;
+                ResolvedCommand::Release
+            }
+            _ if *estopped => ResolvedCommand::Idle,
+            _ => goal.map_or(ResolvedCommand::Idle, ResolvedCommand::Goal),
+        }
+    }
+}
+
+// `CuArray` only decodes without context, so goals are frozen as plain
+// vectors: `None` for no goal, `Some(None)` for a hold, and the group slots
+// alongside the positions for a group write.
+type FrozenGoal = (Vec<f32>, Option<Vec<u8>>);
+
+impl Encode for PendingCommands {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        Encode::encode(&self.estop, encoder)?;
+        let goal = self.goal.as_ref().map(|goal| match goal {
+            GoalCommand::Positions(positions) => Some((positions.as_slice().to_vec(), None)),
+            GoalCommand::Group { slots, positions } => Some((
+                positions.as_slice().to_vec(),
+                Some(slots.as_slice().to_vec()),
+            )),
+            GoalCommand::HoldPresent => None,
+        });
+        Encode::encode(&goal, encoder)
+    }
+}
+
+impl<Context> Decode<Context> for PendingCommands {
+    fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let estop = Decode::decode(decoder)?;
+        let goal: Option<Option<FrozenGoal>> = Decode::decode(decoder)?;
+        let goal = goal.map(|goal| match goal {
+            Some((values, slots)) => {
+                let mut positions = JointPositions::new();
+                positions.fill_from_iter(values);
+                match slots {
+                    Some(slots) => GoalCommand::Group {
+                        slots: slots.into_iter().take(MAX_SERVOS).collect(),
+                        positions,
+                    },
+                    None => GoalCommand::Positions(positions),
+                }
+            }
+            None => GoalCommand::HoldPresent,
+        });
+        Ok(Self { estop, goal })
+    }
+}
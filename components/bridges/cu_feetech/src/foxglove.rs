@@ -0,0 +1,686 @@
+//! Live stream of positions and diagnostics to Foxglove.
+//!
+//! With `"foxglove_port"` set the bridge serves the Foxglove WebSocket
+//! protocol (subprotocol [`SUBPROTOCOL`]) on that port, on the interface
+//! given by `"foxglove_host"` (default `127.0.0.1`).  Port 0 picks a free
+//! one, reported by [`FeetechBridge::foxglove_addr`](crate::FeetechBridge::foxglove_addr).
+//! In Foxglove, open a "Foxglove WebSocket" connection to
+//! `ws://<host>:<port>`.
+//!
+//! Two channels are advertised, both JSON encoded with a JSON schema:
+//!
+//! - [`POSITIONS_TOPIC`] ([`POSITIONS_SCHEMA`]): the positions of every
+//!   polled cycle once the bridge is ready, as a [`PositionsSample`]: `seq`,
+//!   `stamp_ns`, the output `units`, the servo `ids` and their `positions`,
+//!   one per slot.
+//! - [`DIAGNOSTICS_TOPIC`] ([`DIAGNOSTICS_SCHEMA`]): a [`DiagnosticsSample`]
+//!   with the health of every servo: error flag names, `stuck`, `skipped`,
+//!   and `load` (0.1 % of max torque), `voltage` (V) and `temperature` (°C),
+//!   which are `null` until diagnostics are read.  Sent on the cycles
+//!   diagnostics are read, or every cycle when `"diagnostics_interval"` is 0.
+//!
+//! Message timestamps are the positions' `stamp_ns`; use `"stamp_clock":
+//! "wall"` or `"ros2"` so they line up with other Foxglove sources.
+//!
+//! Clients can connect and disconnect at any time.  Each one is sent the
+//! server info and channel list on connect, then only the channels it
+//! subscribed to.  A client that closes, drops the connection, or does not
+//! take a write within [`WRITE_TIMEOUT`] is removed.  Samples reach the
+//! clients through a background writer thread and a bounded queue, and are
+//! dropped when the queue is full, so a slow client never stalls the bridge
+//! cycle.  Nothing is queued while no client is subscribed to a channel.
+//!
+//! Only the server side of the protocol needed to stream is implemented:
+//! `subscribe` and `unsubscribe`.  Client publishing, parameters and
+//! services are not advertised.
+
+use crate::calibration::Units;
+use crate::health::ServoHealth;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use cu29::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// WebSocket subprotocol a client must offer.
+pub const SUBPROTOCOL: &str = "foxglove.websocket.v1";
+
+/// Default `"foxglove_host"`.
+pub const DEFAULT_FOXGLOVE_HOST: &str = "127.0.0.1";
+
+/// Topic of the positions channel.
+pub const POSITIONS_TOPIC: &str = "/feetech/positions";
+
+/// Topic of the diagnostics channel.
+pub const DIAGNOSTICS_TOPIC: &str = "/feetech/diagnostics";
+
+/// JSON schema of [`POSITIONS_TOPIC`] messages ([`PositionsSample`]).
+pub const POSITIONS_SCHEMA: &str = r#"{
+  "title": "cu_feetech.JointPositions",
+  "type": "object",
+  "properties": {
+    "seq": { "type": "integer", "description": "Bridge cycle sequence number" },
+    "stamp_ns": { "type": "integer", "description": "Read time on the configured stamp clock, ns" },
+    "units": { "type": "string", "enum": ["raw", "deg", "rad", "normalize"] },
+    "ids": { "type": "array", "items": { "type": "integer" }, "description": "Bus ID per slot" },
+    "positions": { "type": "array", "items": { "type": "number" }, "description": "Present position per slot, in units" }
+  },
+  "required": ["seq", "stamp_ns", "units", "ids", "positions"]
+}"#;
+
+/// JSON schema of [`DIAGNOSTICS_TOPIC`] messages ([`DiagnosticsSample`]).
+pub const DIAGNOSTICS_SCHEMA: &str = r#"{
+  "title": "cu_feetech.Diagnostics",
+  "type": "object",
+  "properties": {
+    "seq": { "type": "integer", "description": "Bridge cycle sequence number" },
+    "stamp_ns": { "type": "integer", "description": "Read time on the configured stamp clock, ns" },
+    "servos": {
+      "type": "array",
+      "items": {
+        "type": "object",
+        "properties": {
+          "id": { "type": "integer", "description": "Bus ID" },
+          "errors": { "type": "array", "items": { "type": "string" }, "description": "Hardware error flags set" },
+          "stuck": { "type": "boolean", "description": "Position frozen while commanded to move" },
+          "skipped": { "type": "boolean", "description": "Left out of goal writes (skip_write_on)" },
+          "load": { "type": ["integer", "null"], "description": "Signed load, 0.1 % of max torque" },
+          "voltage": { "type": ["number", "null"], "description": "Supply voltage, V" },
+          "temperature": { "type": ["integer", "null"], "description": "Internal temperature, °C" }
+        },
+        "required": ["id", "errors", "stuck", "skipped", "load", "voltage", "temperature"]
+      }
+    }
+  },
+  "required": ["seq", "stamp_ns", "servos"]
+}"#;
+
+/// A client that does not take a write within this time is dropped.
+pub const WRITE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Time a new connection has to complete the WebSocket handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How often the accept loop checks for shutdown.
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Samples waiting for the writer thread before new ones are dropped.
+const QUEUE_DEPTH: usize = 64;
+
+/// Largest handshake request or client frame accepted, in bytes.
+const MAX_CLIENT_MESSAGE: usize = 64 * 1024;
+
+/// GUID appended to the client key for `Sec-WebSocket-Accept` (RFC 6455).
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Foxglove binary opcode of a message on a subscription.
+const MESSAGE_DATA: u8 = 0x01;
+
+mod opcode {
+    pub const TEXT: u8 = 0x1;
+    pub const BINARY: u8 = 0x2;
+    pub const CLOSE: u8 = 0x8;
+    pub const PING: u8 = 0x9;
+    pub const PONG: u8 = 0xA;
+}
+
+/// Advertised channels, indexed by channel ID - 1.
+const CHANNELS: [(&str, &str, &str); 2] = [
+    (
+        POSITIONS_TOPIC,
+        "cu_feetech.JointPositions",
+        POSITIONS_SCHEMA,
+    ),
+    (
+        DIAGNOSTICS_TOPIC,
+        "cu_feetech.Diagnostics",
+        DIAGNOSTICS_SCHEMA,
+    ),
+];
+const POSITIONS_CHANNEL: u32 = 1;
+const DIAGNOSTICS_CHANNEL: u32 = 2;
+
+/// One positions sample, as sent on [`POSITIONS_TOPIC`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PositionsSample {
+    pub seq: u64,
+    pub stamp_ns: u64,
+    pub units: Units,
+    pub ids: Vec<u8>,
+    pub positions: Vec<f32>,
+}
+
+/// Health of every servo, as sent on [`DIAGNOSTICS_TOPIC`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiagnosticsSample {
+    pub seq: u64,
+    pub stamp_ns: u64,
+    pub servos: Vec<ServoStatus>,
+}
+
+/// One servo in a [`DiagnosticsSample`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServoStatus {
+    pub id: u8,
+    pub errors: Vec<String>,
+    pub stuck: bool,
+    pub skipped: bool,
+    pub load: Option<i16>,
+    pub voltage: Option<f32>,
+    pub temperature: Option<u8>,
+}
+
+impl From<&ServoHealth> for ServoStatus {
+    fn from(health: &ServoHealth) -> Self {
+        let names = health.errors.names();
+        Self {
+            id: health.id,
+            errors: names
+                .split(',')
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+                .collect(),
+            stuck: health.stuck,
+            skipped: health.skipped,
+            load: health.diagnostics.map(|d| d.load),
+            voltage: health.diagnostics.map(|d| d.voltage as f32 / 10.0),
+            temperature: health.diagnostics.map(|d| d.temperature),
+        }
+    }
+}
+
+/// A sample queued for the writer thread.
+enum Sample {
+    Positions(PositionsSample),
+    Diagnostics(DiagnosticsSample),
+}
+
+/// A connected client that completed the handshake.
+struct Client {
+    id: u64,
+    stream: TcpStream,
+    /// `(subscription ID, channel ID)` pairs chosen by the client.
+    subscriptions: Vec<(u32, u32)>,
+}
+
+/// State shared by the server threads.
+struct Shared {
+    clients: Mutex<Vec<Client>>,
+    /// Subscriptions per channel, for a lock-free check on the cycle path.
+    subscribers: [AtomicUsize; CHANNELS.len()],
+    next_client: AtomicU64,
+    shutdown: AtomicBool,
+}
+
+impl Shared {
+    fn clients(&self) -> MutexGuard<'_, Vec<Client>> {
+        self.clients.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Recount subscriptions after clients or subscriptions changed.
+    fn recount(&self, clients: &[Client]) {
+        for (i, count) in self.subscribers.iter().enumerate() {
+            let channel = i as u32 + 1;
+            let n = clients
+                .iter()
+                .flat_map(|c| &c.subscriptions)
+                .filter(|(_, ch)| *ch == channel)
+                .count();
+            count.store(n, Ordering::Relaxed);
+        }
+    }
+
+    fn remove(&self, id: u64) {
+        let mut clients = self.clients();
+        clients.retain(|c| c.id != id);
+        self.recount(&clients);
+    }
+}
+
+/// Foxglove WebSocket server streaming the bridge's samples.
+pub struct FoxgloveServer {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+    samples: Option<SyncSender<Sample>>,
+    accept: Option<JoinHandle<()>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl core::fmt::Debug for FoxgloveServer {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("FoxgloveServer")
+            .field("addr", &self.addr)
+            .finish_non_exhaustive()
+    }
+}
+
+impl FoxgloveServer {
+    /// Listen on `addr` and start the accept and writer threads.
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let shared = Arc::new(Shared {
+            clients: Mutex::new(Vec::new()),
+            subscribers: Default::default(),
+            next_client: AtomicU64::new(1),
+            shutdown: AtomicBool::new(false),
+        });
+        let (samples, rx) = mpsc::sync_channel(QUEUE_DEPTH);
+        let writer = {
+            let shared = shared.clone();
+            std::thread::Builder::new()
+                .name("feetech-foxglove-writer".into())
+                .spawn(move || write_samples(&shared, rx))?
+        };
+        let accept = {
+            let shared = shared.clone();
+            std::thread::Builder::new()
+                .name("feetech-foxglove".into())
+                .spawn(move || accept_clients(&shared, listener))?
+        };
+        Ok(Self {
+            addr,
+            shared,
+            samples: Some(samples),
+            accept: Some(accept),
+            writer: Some(writer),
+        })
+    }
+
+    /// Address the server listens on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Number of connected clients.
+    pub fn clients(&self) -> usize {
+        self.shared.clients().len()
+    }
+
+    /// `true` when a client is subscribed to the positions channel.
+    pub fn wants_positions(&self) -> bool {
+        self.wants(POSITIONS_CHANNEL)
+    }
+
+    /// `true` when a client is subscribed to the diagnostics channel.
+    pub fn wants_diagnostics(&self) -> bool {
+        self.wants(DIAGNOSTICS_CHANNEL)
+    }
+
+    fn wants(&self, channel: u32) -> bool {
+        self.shared.subscribers[channel as usize - 1].load(Ordering::Relaxed) > 0
+    }
+
+    /// Queue a positions sample; dropped if the writer is behind.
+    pub fn send_positions(&self, sample: PositionsSample) {
+        self.queue(Sample::Positions(sample));
+    }
+
+    /// Queue a diagnostics sample; dropped if the writer is behind.
+    pub fn send_diagnostics(&self, sample: DiagnosticsSample) {
+        self.queue(Sample::Diagnostics(sample));
+    }
+
+    fn queue(&self, sample: Sample) {
+        if let Some(samples) = &self.samples {
+            let _ = samples.try_send(sample);
+        }
+    }
+}
+
+impl Drop for FoxgloveServer {
+    fn drop(&mut self) {
+        self.shared.shutdown.store(true, Ordering::Relaxed);
+        // Ends the writer loop.
+        self.samples = None;
+        if let Some(accept) = self.accept.take() {
+            let _ = accept.join();
+        }
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+        // Unblocks the client reader threads, which then exit.
+        for client in self.shared.clients().drain(..) {
+            let _ = client.stream.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+/// Accept loop: one thread per connection until shutdown.
+fn accept_clients(shared: &Arc<Shared>, listener: TcpListener) {
+    while !shared.shutdown.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, peer)) => {
+                let shared = shared.clone();
+                let spawned = std::thread::Builder::new()
+                    .name("feetech-foxglove-client".into())
+                    .spawn(move || serve_client(&shared, stream, peer));
+                if let Err(e) = spawned {
+                    warning!(
+                        "FeetechBridge: failed to start a Foxglove client thread: {}",
+                        e.to_string()
+                    );
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                std::thread::sleep(ACCEPT_POLL_INTERVAL);
+            }
+            Err(e) => {
+                debug!("FeetechBridge: Foxglove accept failed: {}", e.to_string());
+                std::thread::sleep(ACCEPT_POLL_INTERVAL);
+            }
+        }
+    }
+}
+
+/// Handshake with one client, then handle its requests until it leaves.
+fn serve_client(shared: &Shared, mut stream: TcpStream, peer: SocketAddr) {
+    let setup = (|| {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+        handshake(&mut stream)?;
+        stream.set_read_timeout(None)?;
+        stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+        stream.write_all(&frame(opcode::TEXT, server_info().as_bytes()))?;
+        stream.write_all(&frame(opcode::TEXT, advertise().as_bytes()))?;
+        stream.try_clone()
+    })();
+    let writer = match setup {
+        Ok(writer) => writer,
+        Err(e) => {
+            debug!(
+                "FeetechBridge: Foxglove handshake with {} failed: {}",
+                peer.to_string(),
+                e.to_string()
+            );
+            return;
+        }
+    };
+    let id = shared.next_client.fetch_add(1, Ordering::Relaxed);
+    shared.clients().push(Client {
+        id,
+        stream: writer,
+        subscriptions: Vec::new(),
+    });
+    info!(
+        "FeetechBridge: Foxglove client {} connected",
+        peer.to_string()
+    );
+
+    // Checked after registering, so a server shutting down meanwhile either
+    // sees this client or is seen here.
+    while !shared.shutdown.load(Ordering::Relaxed) {
+        let Ok((op, payload)) = read_frame(&mut stream) else {
+            break;
+        };
+        match op {
+            opcode::TEXT => handle_request(shared, id, &payload),
+            opcode::PING => reply(shared, id, &frame(opcode::PONG, &payload)),
+            opcode::CLOSE => {
+                reply(shared, id, &frame(opcode::CLOSE, &[]));
+                break;
+            }
+            _ => {}
+        }
+    }
+    shared.remove(id);
+    let _ = stream.shutdown(Shutdown::Both);
+    info!(
+        "FeetechBridge: Foxglove client {} disconnected",
+        peer.to_string()
+    );
+}
+
+/// Write `bytes` to client `id`, under the client lock so it does not
+/// interleave with the writer thread.
+fn reply(shared: &Shared, id: u64, bytes: &[u8]) {
+    if let Some(client) = shared.clients().iter_mut().find(|c| c.id == id) {
+        let _ = client.stream.write_all(bytes);
+    }
+}
+
+/// A client request (JSON text frame).
+#[derive(Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+enum Request {
+    Subscribe {
+        subscriptions: Vec<Subscription>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Unsubscribe {
+        subscription_ids: Vec<u32>,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Subscription {
+    id: u32,
+    channel_id: u32,
+}
+
+fn handle_request(shared: &Shared, id: u64, payload: &[u8]) {
+    // Unknown ops (e.g. parameters) are not advertised and are ignored.
+    let Ok(request) = serde_json::from_slice::<Request>(payload) else {
+        return;
+    };
+    let mut clients = shared.clients();
+    let Some(client) = clients.iter_mut().find(|c| c.id == id) else {
+        return;
+    };
+    match request {
+        Request::Subscribe { subscriptions } => {
+            for sub in subscriptions {
+                let known = (1..=CHANNELS.len() as u32).contains(&sub.channel_id);
+                let taken = client.subscriptions.iter().any(|(s, _)| *s == sub.id);
+                if known && !taken {
+                    client.subscriptions.push((sub.id, sub.channel_id));
+                }
+            }
+        }
+        Request::Unsubscribe { subscription_ids } => {
+            client
+                .subscriptions
+                .retain(|(s, _)| !subscription_ids.contains(s));
+        }
+    }
+    shared.recount(&clients);
+}
+
+/// Writer loop: serialize each sample once and send it to its subscribers.
+fn write_samples(shared: &Shared, samples: Receiver<Sample>) {
+    for sample in samples {
+        let encoded = match &sample {
+            Sample::Positions(s) => (POSITIONS_CHANNEL, s.stamp_ns, serde_json::to_vec(s)),
+            Sample::Diagnostics(s) => (DIAGNOSTICS_CHANNEL, s.stamp_ns, serde_json::to_vec(s)),
+        };
+        let (channel, stamp_ns, Ok(json)) = encoded else {
+            continue;
+        };
+        let mut clients = shared.clients();
+        let before = clients.len();
+        clients.retain_mut(|client| {
+            let alive = client
+                .subscriptions
+                .iter()
+                .filter(|(_, ch)| *ch == channel)
+                .all(|&(sub, _)| {
+                    let message = message_data(sub, stamp_ns, &json);
+                    client
+                        .stream
+                        .write_all(&frame(opcode::BINARY, &message))
+                        .is_ok()
+                });
+            if !alive {
+                let _ = client.stream.shutdown(Shutdown::Both);
+            }
+            alive
+        });
+        if clients.len() != before {
+            debug!(
+                "FeetechBridge: dropped {} Foxglove client(s) that stopped reading",
+                before - clients.len()
+            );
+            shared.recount(&clients);
+        }
+    }
+}
+
+/// Complete the server side of the WebSocket opening handshake.
+fn handshake(stream: &mut TcpStream) -> io::Result<()> {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+        if request.len() > MAX_CLIENT_MESSAGE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "handshake too large",
+            ));
+        }
+        let n = stream.read(&mut buf)?;
+        if n == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        request.extend_from_slice(&buf[..n]);
+    }
+    let request = String::from_utf8_lossy(&request);
+    let header = |name: &str| {
+        request.lines().skip(1).find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.trim()
+                .eq_ignore_ascii_case(name)
+                .then(|| value.trim().to_string())
+        })
+    };
+    let offered = header("Sec-WebSocket-Protocol").unwrap_or_default();
+    let (Some(key), true) = (
+        header("Sec-WebSocket-Key"),
+        offered.split(',').any(|p| p.trim() == SUBPROTOCOL),
+    ) else {
+        stream.write_all(b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n")?;
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("not a {SUBPROTOCOL} WebSocket request"),
+        ));
+    };
+    write!(
+        stream,
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\nSec-WebSocket-Protocol: {SUBPROTOCOL}\r\n\r\n",
+        accept_key(&key)
+    )
+}
+
+/// `Sec-WebSocket-Accept` value for a client's `Sec-WebSocket-Key`.
+pub fn accept_key(key: &str) -> String {
+    let mut sha = sha1_smol::Sha1::new();
+    sha.update(key.as_bytes());
+    sha.update(WEBSOCKET_GUID.as_bytes());
+    BASE64.encode(sha.digest().bytes())
+}
+
+/// Unmasked (server to client) WebSocket frame.
+fn frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(payload.len() + 10);
+    out.push(0x80 | opcode);
+    match payload.len() {
+        n if n < 126 => out.push(n as u8),
+        n if n <= u16::MAX as usize => {
+            out.push(126);
+            out.extend_from_slice(&(n as u16).to_be_bytes());
+        }
+        n => {
+            out.push(127);
+            out.extend_from_slice(&(n as u64).to_be_bytes());
+        }
+    }
+    out.extend_from_slice(payload);
+    out
+}
+
+/// Read one WebSocket frame, unmasking it if needed.  Returns the opcode
+/// and payload.
+pub(crate) fn read_frame(stream: &mut impl Read) -> io::Result<(u8, Vec<u8>)> {
+    let mut head = [0u8; 2];
+    stream.read_exact(&mut head)?;
+    let opcode = head[0] & 0x0F;
+    let len = match head[1] & 0x7F {
+        126 => {
+            let mut len = [0u8; 2];
+            stream.read_exact(&mut len)?;
+            u16::from_be_bytes(len) as u64
+        }
+        127 => {
+            let mut len = [0u8; 8];
+            stream.read_exact(&mut len)?;
+            u64::from_be_bytes(len)
+        }
+        n => n as u64,
+    };
+    if len > MAX_CLIENT_MESSAGE as u64 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "frame too large",
+        ));
+    }
+    let mask = if head[1] & 0x80 != 0 {
+        let mut mask = [0u8; 4];
+        stream.read_exact(&mut mask)?;
+        Some(mask)
+    } else {
+        None
+    };
+    let mut payload = vec![0u8; len as usize];
+    stream.read_exact(&mut payload)?;
+    if let Some(mask) = mask {
+        for (i, byte) in payload.iter_mut().enumerate() {
+            *byte ^= mask[i % 4];
+        }
+    }
+    Ok((opcode, payload))
+}
+
+/// Foxglove `messageData` payload: opcode, subscription ID, timestamp, data.
+fn message_data(subscription: u32, stamp_ns: u64, data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(13 + data.len());
+    out.push(MESSAGE_DATA);
+    out.extend_from_slice(&subscription.to_le_bytes());
+    out.extend_from_slice(&stamp_ns.to_le_bytes());
+    out.extend_from_slice(data);
+    out
+}
+
+fn server_info() -> String {
+    serde_json::json!({
+        "op": "serverInfo",
+        "name": "cu-feetech",
+        "capabilities": [],
+        "metadata": {},
+    })
+    .to_string()
+}
+
+fn advertise() -> String {
+    let channels: Vec<_> = CHANNELS
+        .iter()
+        .enumerate()
+        .map(|(i, (topic, schema_name, schema))| {
+            serde_json::json!({
+                "id": i + 1,
+                "topic": topic,
+                "encoding": "json",
+                "schemaName": schema_name,
+                "schema": schema,
+                "schemaEncoding": "jsonschema",
+            })
+        })
+        .collect();
+    serde_json::json!({ "op": "advertise", "channels": channels }).to_string()
+}
@@ -0,0 +1,84 @@
+//! Named servo groups.
+//!
+//! Groups let application code address a subset of the bus by name (e.g.
+//! `"arm"` and `"gripper"`) instead of by slot index.  They are declared in
+//! the bridge config as a map from group name to a list of servo bus IDs:
+//!
+//! ```ron
+//! "groups": {
+//!     "arm": [1, 2, 3, 4, 5],
+//!     "gripper": [6],
+//! },
+//! ```
+//!
+//! Every ID must belong to a configured servo (`servo0` .. `servo7`).
+
+use crate::messages::MAX_SERVOS;
+use cu29::prelude::*;
+use heapless::Vec as HeaplessVec;
+use std::collections::BTreeMap;
+
+/// A named subset of the configured servos.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServoGroup {
+    /// Group name as written in the config.
+    pub name: String,
+    /// Slot indices (into the bridge's servo list), in the order given in the config.
+    pub slots: HeaplessVec<u8, MAX_SERVOS>,
+}
+
+/// All groups configured on a bridge, sorted by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServoGroups {
+    groups: Vec<ServoGroup>,
+}
+
+impl ServoGroups {
+    /// Build groups from a name → bus IDs map, resolving IDs to slots in `ids`.
+    ///
+    /// Fails if a group is empty, lists an ID twice, or names an ID that is
+    /// not one of the configured servos.
+    pub fn from_ids(config: &BTreeMap<String, Vec<u8>>, ids: &[u8]) -> CuResult<Self> {
+        let mut groups = Vec::with_capacity(config.len());
+        for (name, members) in config {
+            if members.is_empty() {
+                return Err(format!("FeetechBridge: servo group \"{name}\" is empty").into());
+            }
+            let mut slots = HeaplessVec::new();
+            for &id in members {
+                let slot = ids.iter().position(|&s| s == id).ok_or_else(|| {
+                    CuError::from(format!(
+                        "FeetechBridge: servo group \"{name}\" references ID {id}, which is not a configured servo"
+                    ))
+                })? as u8;
+                if slots.contains(&slot) {
+                    return Err(format!(
+                        "FeetechBridge: servo group \"{name}\" lists ID {id} more than once"
+                    )
+                    .into());
+                }
+                // Cannot overflow: slots are unique and there are at most MAX_SERVOS of them.
+                let _ = slots.push(slot);
+            }
+            groups.push(ServoGroup {
+                name: name.clone(),
+                slots,
+            });
+        }
+        Ok(Self { groups })
+    }
+
+    /// Look up a group by name.
+    pub fn get(&self, name: &str) -> Option<&ServoGroup> {
+        self.groups.iter().find(|g| g.name == name)
+    }
+
+    /// Iterate over all groups, sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = &ServoGroup> {
+        self.groups.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+}
@@ -0,0 +1,169 @@
+//! Per-servo health monitoring.
+//!
+//! Some failures never show up as a read error.  A servo whose firmware
+//! stops updating `PRESENT_POSITION` keeps answering every poll, just with
+//! the same value.  The [`StuckDetector`] catches this: a servo is flagged
+//! as stuck when its present position has not changed for `cycles`
+//! consecutive reads while its last commanded goal is more than `threshold`
+//! raw ticks away.  The flag clears as soon as the reading moves again or
+//! the goal comes within `threshold`.
+//!
+//! Every status packet also carries the servo's hardware error flags, kept
+//! as a [`ServoError`] per servo.  Flags listed in `"skip_write_on"` make the
+//! bridge leave that servo out of goal writes until they clear.
+//!
+//! Slower-moving [`ServoDiagnostics`] (load, voltage, temperature) are read
+//! every `"diagnostics_interval"` cycles rather than every cycle.
+//! `"diagnostics_phase"` shifts which cycle of the interval they are read on
+//! (see [`diagnostics_due`]), so they can be kept off cycles that are already
+//! busy and the extra bus time does not line up into a periodic spike.
+
+use bincode::{Decode, Encode};
+use core::str::FromStr;
+
+/// Hardware error flags from the error byte of a status packet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ServoError(pub u8);
+
+impl ServoError {
+    /// Input voltage out of range.
+    pub const VOLTAGE: Self = Self(0x01);
+    /// Position sensor (magnetic encoder) fault.
+    pub const ANGLE: Self = Self(0x02);
+    /// Internal temperature too high.
+    pub const OVERHEAT: Self = Self(0x04);
+    /// Motor current too high.
+    pub const OVERCURRENT: Self = Self(0x08);
+    /// Load above the torque limit for too long.
+    pub const OVERLOAD: Self = Self(0x20);
+
+    const NAMES: [(Self, &'static str); 5] = [
+        (Self::VOLTAGE, "voltage"),
+        (Self::ANGLE, "angle"),
+        (Self::OVERHEAT, "overheat"),
+        (Self::OVERCURRENT, "overcurrent"),
+        (Self::OVERLOAD, "overload"),
+    ];
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// `true` if any flag of `other` is set in `self`.
+    pub fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// Union of two flag sets.
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// Comma-separated names of the known flags set, for logging.
+    pub fn names(self) -> String {
+        Self::NAMES
+            .iter()
+            .filter(|(flag, _)| self.intersects(*flag))
+            .map(|(_, name)| *name)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl FromStr for ServoError {
+    type Err = ();
+
+    /// Parse a single flag name, e.g. `"overheat"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::NAMES
+            .iter()
+            .find(|(_, name)| *name == s)
+            .map(|(flag, _)| *flag)
+            .ok_or(())
+    }
+}
+
+/// Default commanded delta, in raw ticks, above which a frozen reading is suspicious.
+pub const DEFAULT_STUCK_THRESHOLD: u16 = 20;
+
+/// Health snapshot of one servo.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ServoHealth {
+    /// Bus ID of the servo.
+    pub id: u8,
+    /// Present position frozen while commanded to move.
+    pub stuck: bool,
+    /// Error flags from the servo's last status packet.
+    pub errors: ServoError,
+    /// Left out of goal writes because of a `"skip_write_on"` flag.
+    pub skipped: bool,
+    /// Last diagnostics read, if diagnostics are enabled and were read.
+    pub diagnostics: Option<ServoDiagnostics>,
+}
+
+/// Slow-changing servo state, read at a decimated rate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ServoDiagnostics {
+    /// Signed load, in 0.1 % of max torque.
+    pub load: i16,
+    /// Supply voltage, in 0.1 V.
+    pub voltage: u8,
+    /// Internal temperature, in °C.
+    pub temperature: u8,
+}
+
+/// `true` when diagnostics should be read on cycle `cycle`.
+///
+/// Diagnostics are read on the cycles where `cycle % interval == phase`.
+/// An `interval` of 0 disables them.
+#[inline]
+pub fn diagnostics_due(cycle: u64, interval: u32, phase: u32) -> bool {
+    interval != 0 && cycle % interval as u64 == phase as u64
+}
+
+/// Stuck detection parameters, shared by all servos.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StuckDetector {
+    /// Unchanged reads needed to flag the servo.
+    pub cycles: u32,
+    /// Minimum goal-to-present distance, in raw ticks.
+    pub threshold: u16,
+}
+
+/// Per-servo history kept by the [`StuckDetector`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Encode, Decode)]
+pub struct StuckState {
+    /// Last raw goal commanded to the servo.
+    pub goal: Option<u16>,
+    /// Present position seen on the previous read.
+    pub last_present: Option<u16>,
+    /// Consecutive reads with an unchanged position and a large goal delta.
+    pub unchanged: u32,
+    /// Whether the servo is currently flagged.
+    pub stuck: bool,
+}
+
+impl StuckDetector {
+    pub fn new(cycles: u32, threshold: u16) -> Self {
+        Self {
+            cycles: cycles.max(1),
+            threshold,
+        }
+    }
+
+    /// Feed one successful position read; returns whether the servo is stuck.
+    pub fn observe(&self, state: &mut StuckState, present: u16) -> bool {
+        let frozen = state.last_present == Some(present);
+        let commanded = state
+            .goal
+            .is_some_and(|goal| goal.abs_diff(present) > self.threshold);
+        if frozen && commanded {
+            state.unchanged = state.unchanged.saturating_add(1);
+        } else {
+            state.unchanged = 0;
+        }
+        state.last_present = Some(present);
+        state.stuck = state.unchanged >= self.cycles;
+        state.stuck
+    }
+}
@@ -0,0 +1,102 @@
+//! Homing to a hard stop.
+//!
+//! Each servo with a configured homing direction is driven slowly toward
+//! that end of its travel until its load spikes, which means it hit a
+//! mechanical stop.  The position where it stalled becomes the servo's
+//! reference: when a `homing_reference` is configured the calibration
+//! center is shifted so the stop lands on that raw tick.
+//!
+//! The motion itself uses position mode with a speed limit (`GOAL_SPEED`)
+//! and a goal at the far end of the range, so no operating-mode switch is
+//! needed.  The servo's `TORQUE_LIMIT` is lowered for the search, so it does
+//! not push into the stop at full torque, and restored afterwards.  Stall
+//! detection only looks at `PRESENT_LOAD`, sampled every
+//! [`HOMING_POLL_INTERVAL_MS`].
+
+use core::str::FromStr;
+
+/// Which end of the travel to home against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HomingDirection {
+    /// Toward decreasing raw ticks.
+    Min,
+    /// Toward increasing raw ticks.
+    Max,
+}
+
+impl FromStr for HomingDirection {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "min" => Ok(Self::Min),
+            "max" => Ok(Self::Max),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Default homing speed, in the servo's `GOAL_SPEED` units (steps/s).
+pub const DEFAULT_HOMING_SPEED: u16 = 200;
+
+/// Default stall threshold, in 0.1 % of max torque (`PRESENT_LOAD` units).
+pub const DEFAULT_HOMING_LOAD: u16 = 300;
+
+/// Default torque limit while homing, in 0.1 % of max torque.
+///
+/// Must stay above the load threshold, or the stall is never detected.
+pub const DEFAULT_HOMING_TORQUE: u16 = 500;
+
+/// Time between two load samples while homing.
+pub const HOMING_POLL_INTERVAL_MS: u64 = 10;
+
+/// Consecutive samples above the threshold needed to call it a stall.
+///
+/// A single high sample happens when the servo accelerates from rest, so one
+/// sample is not enough.
+pub const HOMING_CONFIRM_SAMPLES: u8 = 3;
+
+/// Default time allowed for a single servo to reach its stop.
+pub const DEFAULT_HOMING_TIMEOUT_MS: u64 = 10_000;
+
+/// Homing parameters for one servo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HomingConfig {
+    pub direction: HomingDirection,
+    /// Speed limit while driving toward the stop.
+    pub speed: u16,
+    /// Load magnitude that counts as hitting the stop.
+    pub load_threshold: u16,
+    /// Torque limit while driving toward the stop.
+    pub torque_limit: u16,
+    /// Raw tick the stop is expected at; the center is shifted to match.
+    pub reference: Option<u16>,
+}
+
+/// Detects a hard stop from a stream of load samples.
+#[derive(Debug, Clone, Copy)]
+pub struct StallDetector {
+    threshold: u16,
+    confirm: u8,
+    above: u8,
+}
+
+impl StallDetector {
+    pub fn new(threshold: u16, confirm: u8) -> Self {
+        Self {
+            threshold,
+            confirm: confirm.max(1),
+            above: 0,
+        }
+    }
+
+    /// Feed one load sample; returns `true` once the stall is confirmed.
+    pub fn observe(&mut self, load: i16) -> bool {
+        if load.unsigned_abs() >= self.threshold {
+            self.above = self.above.saturating_add(1);
+        } else {
+            self.above = 0;
+        }
+        self.above >= self.confirm
+    }
+}
@@ -20,8 +20,17 @@
 //!
 //! | Direction | Channel id         | Payload                       | Description                        |
 //! |-----------|--------------------|-------------------------------|------------------------------------|
-//! | Rx        | `positions`        | [`JointPositions`]     | Present positions read from servos |
+//! | Rx        | `positions`        | [`JointPositions`]     | Present positions read from servos, with the cycle sequence number |
+//! | Rx        | `velocities`       | [`JointVelocities`](messages::JointVelocities) | Present speeds read from servos |
+//! | Rx        | `profile`          | [`CycleProfile`](messages::CycleProfile) | Per-phase timing of the previous cycle |
 //! | Tx        | `goal_positions`   | [`JointPositions`]     | Goal positions written to servos   |
+//! | Tx        | `estop`            | [`EStop`](messages::EStop) | Emergency stop: cut torque and ignore goals |
+//! | Tx        | `ros_clock`        | [`RosTime`](messages::RosTime) | ROS2 `/clock`, for `"stamp_clock": "ros2"` |
+//!
+//! When any Rx channel other than `profile` is connected the bus is polled
+//! once per cycle in [`preprocess`](CuBridge::preprocess); every Rx channel
+//! then publishes from that same sample, stamped with the time it was read.
+//! Connecting only `profile` times the bridge without adding bus traffic.
 //!
 //! # Position values
 //!
@@ -35,12 +44,261 @@
 //! | `"rad"`       | Radians relative to calibration center.         | Yes |
 //! | `"normalize"` | [-1, 1] over calibrated min..max (same scale for leader/follower). | Yes |
 //!
+//! `"units"` applies to both directions.  To decouple them, `"output_units"`
+//! overrides the unit of published positions and `"input_units"` the unit of
+//! `goal_positions`, e.g. publish degrees for humans while a low-level
+//! controller commands raw ticks.
+//!
 //! When using `"deg"`, `"rad"`, or `"normalize"`, set `"calibration_file"` to the path of a
 //! JSON file generated by the `feetech-calibrate` tool.  The center (zero) of
 //! each servo is the midpoint of its calibrated min/max range.  Optionally
 //! set `"ticks_per_rev"` (raw units per 360°); the value is model-dependent
 //! (default 4096, e.g. for STS3215).
 //!
+//! For quick tests the ranges can instead be given inline, per servo slot,
+//! with `"calibration_min<i>"` and `"calibration_max<i>"`.  They are checked
+//! the same way as a file (every servo needs an entry, and a non-empty range
+//! for `"normalize"`) and are only used when no `"calibration_file"` is set:
+//! a file takes precedence.
+//!
+//! # Velocity values
+//!
+//! Velocities come from each servo's `PRESENT_SPEED` register (raw steps/s,
+//! sign in bit 15), read in the same request as the position when the
+//! `velocities` channel is connected.  They are not derived from successive
+//! positions, so they carry no differentiation noise or dependence on the
+//! cycle rate.  `"velocity_units"` (default: the position output unit) scales
+//! them per second:
+//!
+//! | Value         | Scale                                   |
+//! |---------------|-----------------------------------------|
+//! | `"raw"`       | steps/s as read                         |
+//! | `"deg"`       | `360 / ticks_per_rev` per step          |
+//! | `"rad"`       | `2π / ticks_per_rev` per step           |
+//! | `"normalize"` | `1 / half_range` per step (needs calibration), matching `"normalize"` positions |
+//!
+//! # Angle wrapping
+//!
+//! With `"wrap_angles": true`, `"deg"` / `"rad"` output is wrapped into
+//! `[-180, 180)` / `[-π, π)`.  Goal positions in the same wrapped range are
+//! unwrapped against the servo's last read present position: the bridge picks
+//! the equivalent angle closest to where the servo currently is.  Commands are
+//! therefore always interpreted as "less than half a turn away from here".
+//! Present positions are read once on [`start`](CuBridge::start), so this also
+//! works without an Rx channel; a wrapped goal for a servo whose position was
+//! never read is refused with an error rather than resolved against 0.
+//! This only affects `"deg"` and `"rad"`: `"wrap_angles"` without either as the
+//! output or input unit is a config error.
+//!
+//! # Stale command guard
+//!
+//! Set `"max_command_age_ms"` to refuse `goal_positions` whose `tov` is older
+//! than that when [`send`](CuBridge::send) runs, e.g. after a pipeline stall.
+//! `"stale_command_action"` selects the reaction:
+//!
+//! - `"hold"` (default): drop the command; servos keep tracking their
+//!   previous goal.
+//! - `"stop"`: drop the command and set every goal to the servo's present
+//!   position, read fresh from the bus, so the arm stops where it is (torque
+//!   stays on).
+//!
+//! Messages without a `tov` are refused as well, since their age cannot be
+//! checked.  For a range `tov` the end of the range is used.  Refusals are
+//! logged as warnings, at most once per second with a count of the ones in
+//! between, so an operator can see why the arm is not moving.
+//!
+//! # Position stamps
+//!
+//! Besides the robot-time `tov`, each [`JointPositions`] on `positions`
+//! carries `stamp_ns`, the read time on the clock chosen with
+//! `"stamp_clock"`: `"robot"` (default), `"wall"` (Unix epoch), or `"ros2"`
+//! (the `/clock` topic, forwarded on the `ros_clock` Tx channel, which must
+//! then be connected).  Use `"wall"` or `"ros2"` when the positions are
+//! correlated with ROS2 data by timestamp.  See [`stamp`] for details.
+//!
+//! # Synchronized writes
+//!
+//! Goals are written with one broadcast SYNC_WRITE by default.  Servos do not
+//! answer a broadcast, so one that misses the packet (e.g. to line noise)
+//! silently keeps its old goal.  `"verify_sync_write": true` reads every
+//! servo's goal back after the sync-write and re-writes the ones that differ
+//! with an individual, acknowledged WRITE, at the cost of one read per servo
+//! per write.
+//!
+//! With `"goal_write": "reg_write"` each servo is sent its goal with
+//! REG_WRITE, which it buffers, and a broadcast ACTION then starts every servo
+//! at once.
+//! `"verify_reg_write"` reads each servo's async-write flag between the two,
+//! so a servo that missed its goal is caught before the move starts:
+//!
+//! - `"off"` (default): send ACTION without checking.
+//! - `"log"`: log the servos that did not latch, then send ACTION.
+//! - `"abort"`: do not send ACTION and return an error, so no servo moves.
+//!   Buffered goals are replaced by the next write.
+//!
+//! The check costs one read per servo per write.  It only tells whether a
+//! goal is buffered, not which one: the buffered value cannot be read back
+//! before ACTION (`GOAL_POSITION` still holds the previous goal), so a goal
+//! corrupted on the wire but passing the packet checksum is not caught.
+//! Servos that stop acknowledging their REG_WRITE are logged as a warning
+//! in every mode, once per outage.
+//!
+//! # Goal smoothing
+//!
+//! Goal positions can be smoothed per joint before they are written: an
+//! exponential low-pass (`"smoothing"`, weight of the new goal in `(0, 1]`)
+//! followed by a slew limit (`"max_step"`, max change per cycle in the output
+//! unit).  Both default to off (`1.0` / `0.0`).  The global keys set the
+//! default for every joint and `"smoothing<i>"` / `"max_step<i>"` override it
+//! for slot `i`, so a fast joint can bypass smoothing with `"smoothing<i>": 1.0`
+//! while noisy ones are filtered.  The filter starts from the joint's present
+//! position, so the first goal after start or after an e-stop is slew-limited
+//! too.  See [`smoothing`].
+//!
+//! # Quantization diagnostics
+//!
+//! Goal positions are rounded to whole ticks before they are written.  Set
+//! `"log_quantization": true` to accumulate, per servo, the difference between
+//! each commanded value and the value its rounded tick represents (see
+//! [`Units::quantization_error`]).  The max and mean error are logged on
+//! [`stop`](CuBridge::stop) and available through
+//! [`FeetechBridge::quantization_stats`].  A large error relative to the
+//! resolution you need means `ticks_per_rev` is too coarse for the unit.
+//!
+//! # Startup home check
+//!
+//! Servos without multi-turn tracking can come back from a power cycle with a
+//! shifted position reference if they were turned while unpowered.  Set
+//! `"home0"` .. `"home7"` to the raw tick each servo is expected to rest at
+//! when the application starts, and `"home_tolerance"` to the allowed
+//! deviation in raw ticks (default 100).  On [`start`](CuBridge::start) the
+//! bridge reads every servo with an expected home and, when the deviation
+//! exceeds the tolerance, either logs a warning (`"home_mismatch": "warn"`,
+//! default) or shifts that servo's calibration center by the deviation
+//! (`"home_mismatch": "offset"`, deg/rad/normalize only).
+//!
+//! # Homing
+//!
+//! Set `"homing0"` .. `"homing7"` to `"min"` or `"max"` to home that servo
+//! against a hard stop on [`start`](CuBridge::start): the servo is driven
+//! slowly toward that end, with a reduced torque limit, until its load stays
+//! above a threshold, then held where it stalled.  Per-servo
+//! `"homing_speed<i>"`, `"homing_torque<i>"` and `"homing_load<i>"` override
+//! the speed limit, torque limit and load threshold; `"homing_reference<i>"` gives
+//! the raw tick the stop should read as, and the calibration center is
+//! shifted to match (deg/rad/normalize only).  `"homing_timeout_ms"` bounds
+//! each servo's search.  See [`homing`] for details.
+//!
+//! # Ready gate
+//!
+//! Set `"ready_after_cycles"` to `N` to withhold the `positions` channel until
+//! `N` consecutive cycles have read every servo successfully.  Until then the
+//! message is published without a payload, so downstream tasks never act on a
+//! partial or garbage first sample.  Once the threshold is reached the gate
+//! stays open for the rest of the run.
+//!
+//! # Sharing a setup
+//!
+//! [`FeetechBridge::export_config`] returns the whole conversion setup (the
+//! three units, `"ticks_per_rev"`, `"wrap_angles"` and the calibration of each
+//! configured servo) as a [`ConversionConfig`](calibration::ConversionConfig),
+//! which saves to a single JSON file.  Point another bridge's
+//! `"conversion_file"` at it, or pass it to [`FeetechBridge::import_config`],
+//! to convert exactly the same way.  The file replaces the individual keys
+//! and is loaded before the rest of the config is checked, so settings that
+//! need calibrated units (`"home_mismatch": "offset"`, `"homing_reference<i>"`)
+//! are validated against it.  It is validated on import: a wrong version,
+//! `ticks_per_rev` of 0, an inverted or duplicate servo range, a configured
+//! servo without the calibration its units need, or raw units while those
+//! settings are on is an error.
+//!
+//! # Auto-calibration
+//!
+//! With `"auto_calibrate": true` present position reads widen the servo's
+//! min/max in the data loaded from `"calibration_file"` (an empty set if the
+//! file does not exist yet).  A range only grows once three consecutive reads
+//! outside it agree within a few ticks, so one corrupt read cannot stretch
+//! it.  Changes are written back atomically (temporary file, then rename)
+//! every `"calibration_save_interval_ms"` when set, by a writer thread so the
+//! cycle never waits on the disk, and on [`stop`](CuBridge::stop); nothing
+//! is written if no range changed.  Conversions keep the ranges loaded at startup, so widened ranges
+//! take effect on the next start.
+//!
+//! # Jog self-check
+//!
+//! [`FeetechBridge::self_check`] jogs each servo `"self_check_jog"` raw ticks
+//! up and down from its present position and returns it there, checking that
+//! the reading followed within `"self_check_tolerance"`.  It reports pass or
+//! fail per servo without failing the bridge; set `"self_check_on_start"` to
+//! run it during [`start`](CuBridge::start), after homing.  See [`self_check`]
+//! for details.
+//!
+//! # Stuck position detection
+//!
+//! Set `"stuck_cycles"` to `N` to flag a servo whose present position reads
+//! the exact same value for `N` consecutive cycles while its last goal is more
+//! than `"stuck_threshold"` raw ticks away (default 20).  Reads keep succeeding
+//! in that failure mode, so it is logged as a warning and surfaced through
+//! [`FeetechBridge::health`] instead.  See [`health`] for details.
+//!
+//! # Command priority
+//!
+//! Tx commands are collected during the cycle and resolved once in
+//! [`postprocess`](CuBridge::postprocess): an engaged e-stop beats a release,
+//! which beats any goal, so a goal arriving in the same cycle as an e-stop
+//! never reaches the servos.  While the e-stop is latched goals are dropped
+//! and [`FeetechBridge::write_group_positions`] is refused; further engage
+//! messages are ignored, so a publisher repeating the e-stop every cycle does
+//! not flood the bus or the log.  See [`commands`] for the full ordering.
+//!
+//! # Skipping faulted servos
+//!
+//! Every read reply carries the servo's hardware error flags (`"voltage"`,
+//! `"angle"`, `"overheat"`, `"overcurrent"`, `"overload"`).  List some of them
+//! in `"skip_write_on"` to leave a servo out of goal writes while it reports
+//! one, instead of stopping the whole arm.  The servo is written to again as
+//! soon as a read shows the flag cleared; both transitions are logged.  Flags
+//! are only refreshed by reads, so this needs an Rx channel connected.  The
+//! current flags are in [`FeetechBridge::health`].
+//!
+//! # Diagnostics
+//!
+//! Set `"diagnostics_interval"` to `N` to read each servo's load, voltage and
+//! temperature every `N` polled cycles, on the cycles where
+//! `seq % N == "diagnostics_phase"`.  Pick a phase that keeps this extra bus
+//! traffic off cycles that are already heavy.  The last values are in
+//! [`FeetechBridge::health`].
+//!
+//! # Cycle profiling
+//!
+//! Connect the `profile` Rx channel, or set `"profile_log_cycles"` to `N` to
+//! log the mean every `N` cycles, to time each cycle with the robot clock,
+//! split into writing packets, waiting for replies, unit conversion,
+//! publishing, and the rest (see [`profile`]).  When neither is set nothing
+//! is timed.
+//!
+//! # Flight recorder
+//!
+//! Set `"flight_recorder_depth"` to `N` to keep the raw positions and read
+//! failures of the last `N` polled cycles in memory.  When a fault occurs
+//! (e-stop engaged, a servo stuck, a servo reporting an overload, or a servo
+//! missing [`DEAD_SERVO_READS`](recorder::DEAD_SERVO_READS) reads in a row)
+//! they are appended to `"flight_recorder_file"`, once per fault however
+//! small `N` is.  See [`recorder`] for the format.
+//!
+//! # Startup sequence
+//!
+//! [`start`](CuBridge::start) runs an ordered list of steps and stops at the
+//! first failure, disabling torque and returning an error that names the
+//! step.  The default order is `check_home`, `homing`, `self_check` (only with
+//! `"self_check_on_start"`), `torque`.  Set `"startup_sequence"` to a list of
+//! step names to change it; `"ping"` and user hooks (listed as
+//! `"hook:<name>"`) can be added.  Hooks come from a
+//! [`StartupHooks`](startup::StartupHooks) set bound to the bridge's optional
+//! `startup_hooks` resource.  A sequence that leaves out a configured
+//! `homing` or `check_home`, or is combined with `"self_check_on_start"`, is
+//! rejected.  See [`startup`].
+//!
 //! # Torque behaviour
 //!
 //! - When **Tx writers are connected** (commander mode) the bridge enables
@@ -49,20 +307,71 @@
 //!   left **disabled** so the arm can be moved freely by hand while positions
 //!   are read back.
 //! - On [`stop`](CuBridge::stop) torque is always disabled for safety.
+//!
+//! # Live Foxglove stream
+//!
+//! Set `"foxglove_port"` to serve positions and servo diagnostics over the
+//! Foxglove WebSocket protocol while the bridge runs (`"foxglove_host"`
+//! picks the interface, default `127.0.0.1`).  The bus is then polled every
+//! cycle even without an Rx channel connected.  Clients can come and go
+//! freely and never slow the cycle down.  See [`foxglove`] for the channels
+//! and their schemas.
 
 pub mod calibration;
+pub mod commands;
+pub mod foxglove;
+pub mod groups;
+pub mod health;
+pub mod homing;
 pub mod messages;
+pub mod profile;
+pub mod recorder;
+pub mod self_check;
+pub mod smoothing;
+pub mod stamp;
+pub mod startup;
 
-use crate::calibration::{CalibrationData, Units};
-use crate::messages::{JointPositions, MAX_SERVOS};
+use crate::calibration::{
+    AutoCalibration, CONVERSION_FORMAT_VERSION, CalibrationData, ConversionConfig,
+    QuantizationStats, ServoCalibration, Units, needs_calibration,
+};
+use crate::commands::{GoalCommand, PendingCommands, ResolvedCommand};
+use crate::foxglove::{
+    DEFAULT_FOXGLOVE_HOST, DiagnosticsSample, FoxgloveServer, PositionsSample, ServoStatus,
+};
+use crate::groups::{ServoGroup, ServoGroups};
+use crate::health::{
+    DEFAULT_STUCK_THRESHOLD, ServoDiagnostics, ServoError, ServoHealth, StuckDetector, StuckState,
+    diagnostics_due,
+};
+use crate::homing::{
+    DEFAULT_HOMING_LOAD, DEFAULT_HOMING_SPEED, DEFAULT_HOMING_TIMEOUT_MS, DEFAULT_HOMING_TORQUE,
+    HOMING_CONFIRM_SAMPLES, HOMING_POLL_INTERVAL_MS, HomingConfig, HomingDirection, StallDetector,
+};
+use crate::messages::{CycleProfile, EStop, JointPositions, JointVelocities, MAX_SERVOS, RosTime};
+use crate::profile::{Phase, PhaseStart, Profiler};
+use crate::recorder::{DEAD_SERVO_READS, DEFAULT_RECORDER_FILE, FlightRecorder, RecorderEntry};
+use crate::self_check::{
+    DEFAULT_JOG_SETTLE_MS, DEFAULT_JOG_SPEED, DEFAULT_JOG_TICKS, DEFAULT_JOG_TOLERANCE,
+    JOG_POLL_INTERVAL_MS, JogConfig, JogResult, JogVerdict, evaluate_jog,
+};
+use crate::smoothing::JointSmoother;
+use crate::stamp::{StampClock, StampSource};
+use crate::startup::{StartupHook, StartupHooks, StartupSequence, StartupState, StartupStep};
+use bincode::de::Decoder;
+use bincode::enc::Encoder;
+use bincode::error::{DecodeError, EncodeError};
+use bincode::{Decode, Encode};
 use cu_linux_resources::LinuxSerialPort;
 use cu29::cubridge::{
     BridgeChannel, BridgeChannelConfig, BridgeChannelInfo, BridgeChannelSet, CuBridge,
 };
 use cu29::prelude::*;
-use cu29::resources;
+use cu29::resource::{Owned, ResourceBindingMap, ResourceBindings, ResourceManager};
 use heapless::Vec as HeaplessVec;
+use std::collections::BTreeMap;
 use std::io::{self, Read, Write};
+use std::net::SocketAddr;
 
 // ===========================================================================
 // Feetech STS/SCS protocol constants
@@ -105,6 +414,33 @@ mod instr {
     pub const RESET: u8 = 0x06;
 }
 
+/// Decode the `PRESENT_SPEED` register into signed steps per second.
+///
+/// Bits 0..=14 hold the magnitude and bit 15 the direction.
+#[inline]
+pub fn decode_speed(raw: u16) -> i16 {
+    let magnitude = (raw & 0x7FFF) as i16;
+    if raw & 0x8000 != 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+/// Decode the `PRESENT_LOAD` register into a signed load.
+///
+/// Bits 0..=9 hold the magnitude in 0.1 % of max torque and bit 10 the
+/// direction.
+#[inline]
+pub fn decode_load(raw: u16) -> i16 {
+    let magnitude = (raw & 0x3FF) as i16;
+    if raw & 0x400 != 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
 /// STS3215 register map (addresses and widths).
 ///
 /// Only the registers relevant to position control are included here.
@@ -123,11 +459,13 @@ mod reg {
     pub const GOAL_POSITION: u8 = 42; // 2 bytes — target position (0..65535)
     pub const GOAL_TIME: u8 = 44; // 2 bytes — time to reach goal (ms)
     pub const GOAL_SPEED: u8 = 46; // 2 bytes — max speed
+    pub const TORQUE_LIMIT: u8 = 48; // 2 bytes — max torque, 0.1 % units
     pub const PRESENT_POSITION: u8 = 56; // 2 bytes — current position (0..65535)
     pub const PRESENT_SPEED: u8 = 58; // 2 bytes — current speed
     pub const PRESENT_LOAD: u8 = 60; // 2 bytes — current load
     pub const PRESENT_VOLTAGE: u8 = 62; // 1 byte  — supply voltage
     pub const PRESENT_TEMPERATURE: u8 = 63; // 1 byte  — internal temperature
+    pub const ASYNC_WRITE_FLAG: u8 = 64; // 1 byte  — 1 while a REG_WRITE is buffered
     pub const MOVING: u8 = 66; // 1 byte  — 1 while in motion
 }
 
@@ -152,18 +490,229 @@ fn compute_checksum(data: &[u8]) -> u8 {
     !sum
 }
 
+/// Build sync-write params that set `GOAL_POSITION` for each `(id, raw)` pair.
+///
+/// Packet params layout:
+/// ```text
+/// [start_address] [bytes_per_servo] [ID_0] [lo_0] [hi_0] [ID_1] …
+/// ```
+///
+/// Returns the number of bytes written to `params`.
+fn build_goal_sync_write(
+    entries: &[(u8, u16)],
+    params: &mut [u8; MAX_PACKET_SIZE - 5],
+) -> CuResult<usize> {
+    let data_len_per_servo: u8 = 2; // 2 bytes for GOAL_POSITION
+    // Max params: 2 (start addr + len) + MAX_SERVOS*3 (ID + 2 bytes data) = 26 bytes
+    let params_size = 2 + entries.len() * 3;
+    if params_size > params.len() {
+        return Err(CuError::from("Feetech: sync-write params too large"));
+    }
+    params[0] = reg::GOAL_POSITION; // start address
+    params[1] = data_len_per_servo;
+    for (k, &(id, raw)) in entries.iter().enumerate() {
+        let offset = 2 + k * 3;
+        params[offset] = id; // servo ID
+        params[offset + 1] = (raw & 0xFF) as u8; // position low byte
+        params[offset + 2] = (raw >> 8) as u8; // position high byte
+    }
+    Ok(params_size)
+}
+
+// ===========================================================================
+// Startup home check
+// ===========================================================================
+
+/// What to do when a servo starts too far from its expected home.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HomeMismatch {
+    /// Log a warning and carry on.
+    #[default]
+    Warn,
+    /// Shift the servo's calibration center by the measured deviation.
+    Offset,
+}
+
+impl core::str::FromStr for HomeMismatch {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "warn" => Ok(Self::Warn),
+            "offset" => Ok(Self::Offset),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Default allowed deviation from the expected home, in raw ticks.
+pub const DEFAULT_HOME_TOLERANCE: u16 = 100;
+
+/// Deviation `present - expected` in raw ticks, or `None` when it is within
+/// `tolerance` (inclusive).
+#[inline]
+pub fn home_deviation(present: u16, expected: u16, tolerance: u16) -> Option<i32> {
+    let delta = present as i32 - expected as i32;
+    (delta.unsigned_abs() > tolerance as u32).then_some(delta)
+}
+
+// ===========================================================================
+// Stale command guard
+// ===========================================================================
+
+/// Reaction to a goal command older than `max_command_age_ms`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StaleCommandAction {
+    /// Drop the command and keep the previous goal.
+    #[default]
+    Hold,
+    /// Drop the command and stop every servo at its present position.
+    Stop,
+}
+
+impl core::str::FromStr for StaleCommandAction {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hold" => Ok(Self::Hold),
+            "stop" => Ok(Self::Stop),
+            _ => Err(()),
+        }
+    }
+}
+
+// ===========================================================================
+// Goal write mode
+// ===========================================================================
+
+/// How goal positions are put on the bus (`"goal_write"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GoalWrite {
+    /// One broadcast SYNC_WRITE packet carrying every goal.
+    #[default]
+    Sync,
+    /// A REG_WRITE per servo, then one broadcast ACTION to start them together.
+    RegWrite,
+}
+
+impl core::str::FromStr for GoalWrite {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sync" => Ok(Self::Sync),
+            "reg_write" => Ok(Self::RegWrite),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Check that every servo latched its REG_WRITE before ACTION (`"verify_reg_write"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VerifyRegWrite {
+    /// Send ACTION without checking.
+    #[default]
+    Off,
+    /// Check, log the servos that did not latch, and send ACTION anyway.
+    Log,
+    /// Check, and withhold ACTION if any servo did not latch.
+    Abort,
+}
+
+impl core::str::FromStr for VerifyRegWrite {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(Self::Off),
+            "log" => Ok(Self::Log),
+            "abort" => Ok(Self::Abort),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Minimum time between two warnings about refused goal commands.
+const REFUSED_GOAL_LOG_INTERVAL_MS: u64 = 1_000;
+
+/// Age of a message at `now`, or `None` when it carries no time of validity.
+///
+/// A `tov` in the future counts as age zero.
+#[inline]
+pub fn command_age(tov: &Tov, now: CuTime) -> Option<CuDuration> {
+    let stamp = match tov {
+        Tov::None => return None,
+        Tov::Time(t) => *t,
+        Tov::Range(range) => range.end,
+    };
+    Some(CuDuration::from_nanos(
+        now.as_nanos().saturating_sub(stamp.as_nanos()),
+    ))
+}
+
+// ===========================================================================
+// Ready gate
+// ===========================================================================
+
+/// Startup gate that opens after `threshold` consecutive full reads.
+///
+/// A "full read" is a cycle where every configured servo answered.  Any
+/// partial cycle resets the streak.  Once the gate is open it stays open.
+/// A `threshold` of 0 means the gate is always open.
+#[derive(Debug, Clone, Copy, Default, Reflect)]
+pub struct ReadyGate {
+    /// Number of consecutive full reads required before publishing.
+    threshold: u32,
+    /// Current streak of full reads, saturating at `threshold`.
+    consecutive: u32,
+}
+
+impl ReadyGate {
+    pub fn new(threshold: u32) -> Self {
+        Self {
+            threshold,
+            consecutive: 0,
+        }
+    }
+
+    /// `true` once the required number of consecutive full reads was seen.
+    #[inline]
+    pub fn is_ready(&self) -> bool {
+        self.consecutive >= self.threshold
+    }
+
+    /// Record the outcome of one cycle and return whether the gate is open.
+    pub fn observe(&mut self, full_read_ok: bool) -> bool {
+        if self.is_ready() {
+            return true;
+        }
+        if full_read_ok {
+            self.consecutive += 1;
+        } else {
+            self.consecutive = 0;
+        }
+        self.is_ready()
+    }
+}
+
 // ===========================================================================
 // Bridge channel declarations
 // ===========================================================================
 
-// Declare the Rx (bridge → task) channel carrying present positions.
+// Declare the Rx (bridge → task) channels carrying present positions.
 rx_channels! {
-    positions => JointPositions
+    positions => JointPositions,
+    velocities => JointVelocities,
+    profile => CycleProfile
 }
 
-// Declare the Tx (task → bridge) channel carrying goal positions.
+// Declare the Tx (task → bridge) channels carrying goal positions, e-stop and
+// the ROS2 clock.
 tx_channels! {
-    goal_positions => JointPositions
+    goal_positions => JointPositions,
+    estop => EStop,
+    ros_clock => RosTime
 }
 
 // ===========================================================================
@@ -171,10 +720,44 @@ tx_channels! {
 // ===========================================================================
 
 // The bridge takes exclusive ownership of a serial port provided by the
-// resource manager (configured in `copperconfig.ron` under `resources`).
-resources!({
-    serial => Owned<LinuxSerialPort>,
-});
+// resource manager (configured in `copperconfig.ron` under `resources`), and
+// optionally of the application's startup hooks.  `resources!` has no
+// optional entries, hence the hand-written binding.
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Binding {
+    Serial,
+    StartupHooks,
+}
+
+pub struct Resources {
+    pub serial: Owned<LinuxSerialPort>,
+    /// Steps for `"hook:<name>"` entries of `"startup_sequence"`, if bound.
+    pub startup_hooks: Option<Owned<StartupHooks>>,
+}
+
+impl<'r> ResourceBindings<'r> for Resources {
+    type Binding = Binding;
+
+    fn from_bindings(
+        manager: &'r mut ResourceManager,
+        mapping: Option<&ResourceBindingMap<Self::Binding>>,
+    ) -> CuResult<Self> {
+        let mapping = mapping.ok_or_else(|| CuError::from("missing resource bindings"))?;
+        let serial = mapping
+            .get(Binding::Serial)
+            .ok_or_else(|| CuError::from("missing `serial` resource binding"))?;
+        let serial = manager.take::<LinuxSerialPort>(serial.typed())?;
+        let startup_hooks = mapping
+            .get(Binding::StartupHooks)
+            .map(|key| manager.take::<StartupHooks>(key.typed()))
+            .transpose()?;
+        Ok(Self {
+            serial,
+            startup_hooks,
+        })
+    }
+}
 
 // ===========================================================================
 // FeetechBridge
@@ -191,7 +774,8 @@ resources!({
 ///
 /// Positions are converted to the unit specified by the `"units"` config key
 /// (`"raw"`, `"deg"`, `"rad"`, or `"normalize"`).  When using `"deg"`, `"rad"`, or
-/// `"normalize"`, a calibration file (`"calibration_file"` key) must be provided.
+/// `"normalize"`, calibration must be provided, from a file (`"calibration_file"`)
+/// or inline (`"calibration_min<i>"` / `"calibration_max<i>"`).
 #[derive(Reflect)]
 #[reflect(from_reflect = false)]
 pub struct FeetechBridge {
@@ -206,36 +790,243 @@ pub struct FeetechBridge {
     /// How many servos are configured (1..=[`MAX_SERVOS`]).
     num_servos: u8,
 
-    /// `true` when at least one Tx (goal_positions) writer is connected.
+    /// `true` when a `goal_positions` writer is connected.
     /// Controls whether torque is enabled at startup:
     /// - `true`  → commander mode: torque ON, servos track goals.
     /// - `false` → follower / teach mode: torque OFF, arm moves freely.
     has_writers: bool,
 
+    /// `true` when at least one Rx channel is connected; the bus is only
+    /// polled each cycle when someone consumes the positions.
+    has_readers: bool,
+
+    /// Sequence number of the current cycle's poll (0 before the first one).
+    cycle_seq: u64,
+
+    /// Robot time at which the current cycle's positions were read.
+    #[reflect(ignore)]
+    last_read_time: CuTime,
+
+    /// `last_read_time` on the configured stamp clock, in nanoseconds.
+    last_read_stamp: u64,
+
+    /// Clock for the `stamp_ns` of published positions (`"stamp_clock"`).
+    #[reflect(ignore)]
+    stamp: StampSource,
+
+    /// `true` once the missing `/clock` has been logged.
+    ros_clock_warned: bool,
+
     /// Cached raw positions from the last `read_all_positions` call.
     /// One entry per configured servo; remaining slots are unused.
     cached_positions: [u16; MAX_SERVOS],
 
-    /// Output unit for published positions.
+    /// `true` once `cached_positions[i]` holds a reading rather than the
+    /// initial 0.
+    position_known: [bool; MAX_SERVOS],
+
+    /// `true` when the `velocities` channel is connected; speeds are then
+    /// read along with positions.
+    read_velocities: bool,
+
+    /// Cached signed speeds (steps/s) from the last `read_all_positions` call.
+    cached_speeds: [i16; MAX_SERVOS],
+
+    /// Unit of published velocities, per second (`"velocity_units"`).
+    #[reflect(ignore)]
+    velocity_units: Units,
+
+    /// Unit of published positions (`"output_units"`, else `"units"`).
     #[reflect(ignore)]
-    units: Units,
+    output_units: Units,
+
+    /// Unit of goal position commands (`"input_units"`, else `"units"`).
+    #[reflect(ignore)]
+    input_units: Units,
 
     /// Per-servo calibration center (raw ticks), indexed by servo slot.
-    /// Only meaningful when `units != Raw`.
+    /// Only meaningful when either unit is not `Raw`.
     centers: [f32; MAX_SERVOS],
 
     /// Ticks per revolution (raw units per 360°) for deg/rad conversion. Model-dependent.
     #[reflect(ignore)]
     ticks_per_rev: u32,
 
-    /// Per-servo half-range (max - min) / 2 for normalize unit. Only used when `units == Normalize`.
+    /// Per-servo half-range (max - min) / 2 for normalize unit. Only used when a unit is `Normalize`.
     #[reflect(ignore)]
     half_ranges: [f32; MAX_SERVOS],
+
+    /// Wrap deg/rad output into a single turn centered on zero.
+    wrap_angles: bool,
+
+    /// Calibration entries of the configured servos, as loaded.
+    #[reflect(ignore)]
+    calibration: CalibrationData,
+
+    /// Goal commands older than this are refused (`"max_command_age_ms"`).
+    #[reflect(ignore)]
+    max_command_age: Option<CuDuration>,
+
+    /// What to do with a refused stale command.
+    #[reflect(ignore)]
+    stale_action: StaleCommandAction,
+
+    /// When a refused goal was last logged.
+    #[reflect(ignore)]
+    refused_logged_at: Option<CuTime>,
+
+    /// Goals refused since the last warning.
+    refused_goals: u32,
+
+    /// SYNC_WRITE or REG_WRITE + ACTION for goal positions.
+    #[reflect(ignore)]
+    goal_write: GoalWrite,
+
+    /// Latch check before ACTION, in REG_WRITE mode.
+    #[reflect(ignore)]
+    verify_reg_write: VerifyRegWrite,
+
+    /// Servos whose last REG_WRITE was not acknowledged, so each outage is
+    /// warned about once.
+    reg_write_unacked: [bool; MAX_SERVOS],
+
+    /// Read goals back after a SYNC_WRITE and re-write the ones that were missed.
+    #[reflect(ignore)]
+    verify_sync_write: bool,
+
+    /// Per-joint goal filters, indexed by servo slot.
+    #[reflect(ignore)]
+    smoothers: [JointSmoother; MAX_SERVOS],
+
+    /// Per-servo round-trip error of commanded goals, when enabled.
+    #[reflect(ignore)]
+    quantization: Option<[QuantizationStats; MAX_SERVOS]>,
+
+    /// Expected raw position at startup per servo slot (`"home0"` ..).
+    #[reflect(ignore)]
+    expected_home: [Option<u16>; MAX_SERVOS],
+
+    /// Allowed deviation from `expected_home`, in raw ticks.
+    home_tolerance: u16,
+
+    /// Reaction to a servo starting outside `home_tolerance`.
+    #[reflect(ignore)]
+    home_mismatch: HomeMismatch,
+
+    /// Per-slot hard-stop homing parameters (`"homing0"` ..).
+    #[reflect(ignore)]
+    homing: [Option<HomingConfig>; MAX_SERVOS],
+
+    /// Time allowed for each servo to find its stop.
+    #[reflect(ignore)]
+    homing_timeout: CuDuration,
+
+    /// Raw position where each homed servo stalled, once homing ran.
+    #[reflect(ignore)]
+    homed_positions: [Option<u16>; MAX_SERVOS],
+
+    /// Named subsets of the configured servos (`"groups"` config key).
+    #[reflect(ignore)]
+    groups: ServoGroups,
+
+    /// Withholds `positions` until enough consecutive full reads were seen.
+    ready_gate: ReadyGate,
+
+    /// Flags servos whose position freezes while commanded to move.
+    #[reflect(ignore)]
+    stuck_detector: Option<StuckDetector>,
+
+    /// Per-servo stuck detection history, indexed by servo slot.
+    #[reflect(ignore)]
+    stuck: [StuckState; MAX_SERVOS],
+
+    /// Calibration widened from live readings and saved back (`"auto_calibrate"`).
+    #[reflect(ignore)]
+    auto_calibration: Option<AutoCalibration>,
+
+    /// Jog self-check parameters.
+    #[reflect(ignore)]
+    jog: JogConfig,
+
+    /// Steps run by `start`, in order (`"startup_sequence"`).
+    #[reflect(ignore)]
+    startup: StartupSequence,
+
+    /// Report of the last jog self-check, one entry per servo.
+    #[reflect(ignore)]
+    self_check_results: Vec<JogResult>,
+
+    /// Commands received this cycle, resolved in `postprocess`.
+    #[reflect(ignore)]
+    pending: PendingCommands,
+
+    /// Latched e-stop: torque is off and goals are ignored.
+    estopped: bool,
+
+    /// Error flags from each servo's last status packet, indexed by slot.
+    #[reflect(ignore)]
+    servo_errors: [ServoError; MAX_SERVOS],
+
+    /// Error flags that exclude a servo from goal writes (`"skip_write_on"`).
+    #[reflect(ignore)]
+    skip_write_on: ServoError,
+
+    /// Servos currently left out of goal writes, indexed by slot.
+    skipping: [bool; MAX_SERVOS],
+
+    /// Read diagnostics every this many cycles; 0 disables them.
+    diagnostics_interval: u32,
+
+    /// Cycle within the interval on which diagnostics are read.
+    diagnostics_phase: u32,
+
+    /// Last diagnostics read per servo slot.
+    #[reflect(ignore)]
+    diagnostics: [Option<ServoDiagnostics>; MAX_SERVOS],
+
+    /// Per-phase cycle timing, when the `profile` channel is connected or
+    /// `"profile_log_cycles"` is set.
+    #[reflect(ignore)]
+    profiler: Option<Profiler>,
+
+    /// Last cycles of raw positions, dumped on fault (`"flight_recorder_depth"`).
+    #[reflect(ignore)]
+    recorder: Option<FlightRecorder>,
+
+    /// File the flight recorder is appended to.
+    #[reflect(ignore)]
+    recorder_file: std::path::PathBuf,
+
+    /// Live Foxglove stream (`"foxglove_port"`), if enabled.
+    #[reflect(ignore)]
+    foxglove: Option<FoxgloveServer>,
 }
 
 impl Freezable for FeetechBridge {
-    // No mutable runtime state beyond what the hardware provides.
-    // Default freeze / thaw (no-op) is fine.
+    fn freeze<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        Encode::encode(&self.ready_gate.consecutive, encoder)?;
+        Encode::encode(&self.cycle_seq, encoder)?;
+        let last_goals: [Option<f32>; MAX_SERVOS] =
+            core::array::from_fn(|i| self.smoothers[i].last);
+        Encode::encode(&last_goals, encoder)?;
+        Encode::encode(&self.stuck, encoder)?;
+        Encode::encode(&self.estopped, encoder)?;
+        Encode::encode(&self.pending, encoder)?;
+        Ok(())
+    }
+
+    fn thaw<D: Decoder>(&mut self, decoder: &mut D) -> Result<(), DecodeError> {
+        self.ready_gate.consecutive = Decode::decode(decoder)?;
+        self.cycle_seq = Decode::decode(decoder)?;
+        let last_goals: [Option<f32>; MAX_SERVOS] = Decode::decode(decoder)?;
+        for (smoother, last) in self.smoothers.iter_mut().zip(last_goals) {
+            smoother.last = last;
+        }
+        self.stuck = Decode::decode(decoder)?;
+        self.estopped = Decode::decode(decoder)?;
+        self.pending = Decode::decode(decoder)?;
+        Ok(())
+    }
 }
 
 // ===========================================================================
@@ -265,9 +1056,13 @@ impl FeetechBridge {
         // Checksum covers everything after the header (ID onward).
         let checksum = compute_checksum(&packet[2..5 + params.len()]);
         packet[5 + params.len()] = checksum;
-        self.port.write_all(&packet[..packet_size])?;
-        self.port.flush()?;
-        Ok(())
+        let started = self.phase_start();
+        let written = self
+            .port
+            .write_all(&packet[..packet_size])
+            .and_then(|()| self.port.flush());
+        self.phase_end(Phase::Write, started);
+        written
     }
 
     /// Read and validate a status packet returned by a servo.
@@ -280,6 +1075,16 @@ impl FeetechBridge {
     /// Returns `(id, error_byte, data)` on success.
     fn read_status_packet(
         &mut self,
+    ) -> io::Result<(u8, u8, HeaplessVec<u8, MAX_STATUS_PACKET_SIZE>)> {
+        let started = self.phase_start();
+        let status = self.read_status_packet_untimed();
+        self.phase_end(Phase::Wait, started);
+        status
+    }
+
+    /// [`read_status_packet`](Self::read_status_packet), without profiling.
+    fn read_status_packet_untimed(
+        &mut self,
     ) -> io::Result<(u8, u8, HeaplessVec<u8, MAX_STATUS_PACKET_SIZE>)> {
         // Read the fixed-size portion: header (2) + id (1) + length (1).
         let mut header = [0u8; 4];
@@ -356,7 +1161,10 @@ impl FeetechBridge {
     ) -> io::Result<HeaplessVec<u8, MAX_STATUS_PACKET_SIZE>> {
         // READ instruction params: [start_address, byte_count].
         self.send_packet(id, instr::READ, &[address, count])?;
-        let (_id, _error, data) = self.read_status_packet()?;
+        let (_id, error, data) = self.read_status_packet()?;
+        if let Some(i) = self.slot_of(id) {
+            self.servo_errors[i] = ServoError(error);
+        }
         Ok(data)
     }
 
@@ -400,16 +1208,63 @@ impl FeetechBridge {
         Ok(u16::from_le_bytes([data[0], data[1]]))
     }
 
+    /// Read present position and speed from one servo in a single request.
+    ///
+    /// The two registers are adjacent, so this costs one round-trip.
+    fn read_present_position_and_speed(&mut self, id: u8) -> CuResult<(u16, i16)> {
+        let data = self
+            .read_register(id, reg::PRESENT_POSITION, 4)
+            .map_err(|e| {
+                CuError::new_with_cause(
+                    &format!(
+                        "Feetech: failed to read position and speed from servo {}",
+                        id
+                    ),
+                    e,
+                )
+            })?;
+        if data.len() < 4 {
+            return Err(format!(
+                "Feetech: short read for position and speed from servo {} (got {} bytes)",
+                id,
+                data.len()
+            )
+            .into());
+        }
+        Ok((
+            u16::from_le_bytes([data[0], data[1]]),
+            decode_speed(u16::from_le_bytes([data[2], data[3]])),
+        ))
+    }
+
     /// Poll present positions from every configured servo into `cached_positions`.
     ///
     /// On a read failure for any individual servo the previously cached value
     /// is kept and a debug message is logged — the bus continues with the
     /// remaining servos.
-    fn read_all_positions(&mut self) -> CuResult<()> {
-        for i in 0..self.num_servos as usize {
-            match self.read_present_position(self.ids[i]) {
-                Ok(raw) => self.cached_positions[i] = raw,
+    ///
+    /// Returns, per servo slot, whether the read failed.
+    fn read_all_positions(&mut self) -> CuResult<[bool; MAX_SERVOS]> {
+        let mut failed = [false; MAX_SERVOS];
+        for (i, failed) in failed.iter_mut().enumerate().take(self.num_servos as usize) {
+            let read = if self.read_velocities {
+                self.read_present_position_and_speed(self.ids[i])
+                    .map(|(raw, speed)| {
+                        self.cached_speeds[i] = speed;
+                        raw
+                    })
+            } else {
+                self.read_present_position(self.ids[i])
+            };
+            match read {
+                Ok(raw) => {
+                    self.cache_position(i, raw);
+                    if let Some(auto) = &mut self.auto_calibration {
+                        auto.observe(self.ids[i], raw);
+                    }
+                }
                 Err(e) => {
+                    *failed = true;
                     debug!(
                         "Feetech: failed to read servo {} (ID {}): {}",
                         i, self.ids[i], e
@@ -417,7 +1272,7 @@ impl FeetechBridge {
                 }
             }
         }
-        Ok(())
+        Ok(failed)
     }
 
     /// Write goal positions to all configured servos using **sync-write**.
@@ -435,358 +1290,4018 @@ impl FeetechBridge {
         // Write at most as many servos as we have configured, even if the
         // payload carries fewer (or more) entries.
         let n = (self.num_servos as usize).min(vals.len());
-        if n == 0 {
-            return Ok(());
-        }
-
-        let data_len_per_servo: u8 = 2; // 2 bytes for GOAL_POSITION
-        // Max params: 2 (start addr + len) + MAX_SERVOS*3 (ID + 2 bytes data) = 26 bytes
-        let params_size = 2 + n * 3;
-        if params_size > MAX_PACKET_SIZE - 5 {
-            return Err(CuError::from("Feetech: sync-write params too large"));
-        }
-        let mut params = [0u8; MAX_PACKET_SIZE - 5];
-        params[0] = reg::GOAL_POSITION; // start address
-        params[1] = data_len_per_servo;
-        let mut offset = 2;
-        for (i, val) in vals.iter().enumerate().take(n) {
-            let param = self.param_for_slot(i);
-            let raw = self.units.to_raw(*val, self.centers[i], param);
-            params[offset] = self.ids[i]; // servo ID
-            params[offset + 1] = (raw & 0xFF) as u8; // position low byte
-            params[offset + 2] = (raw >> 8) as u8; // position high byte
-            offset += 3;
-        }
-
-        self.send_packet(BROADCAST_ID, instr::SYNC_WRITE, &params[..params_size])
-            .map_err(|e| CuError::new_with_cause("Feetech: sync-write failed", e))?;
-        Ok(())
+        let goals: HeaplessVec<(usize, f32), MAX_SERVOS> =
+            vals.iter().copied().enumerate().take(n).collect();
+        self.write_goals(&goals)
     }
 
-    /// Enable or disable torque on a single servo.
+    /// Convert `(slot, value)` goals to raw ticks and write them.
     ///
-    /// When torque is **enabled** the servo actively holds its position.
-    /// When **disabled** the servo can be moved freely by hand.
-    #[allow(dead_code)]
-    pub fn set_torque(&mut self, id: u8, enable: bool) -> io::Result<()> {
-        self.write_register(id, reg::TORQUE_ENABLE, &[enable as u8])
+    /// The path every goal takes: unwrapping, smoothing and quantization in
+    /// [`goal_to_raw`](Self::goal_to_raw), then [`sync_write_raw`](Self::sync_write_raw),
+    /// which leaves out servos in the skip set.
+    fn write_goals(&mut self, goals: &[(usize, f32)]) -> CuResult<()> {
+        let mut entries: HeaplessVec<(u8, u16), MAX_SERVOS> = HeaplessVec::new();
+        let started = self.phase_start();
+        for &(i, value) in goals {
+            let raw = self.goal_to_raw(i, value)?;
+            let _ = entries.push((self.ids[i], raw));
+        }
+        self.phase_end(Phase::Convert, started);
+        self.sync_write_raw(&entries)
     }
 
-    /// Parameter for from_raw/to_raw: ticks_per_rev for Deg/Rad, half_ranges[i] for Normalize.
-    #[inline]
-    fn param_for_slot(&self, i: usize) -> f32 {
-        if self.units == Units::Normalize {
-            let hr = self.half_ranges[i];
-            if hr > 0.0 { hr } else { 1.0 } // avoid div-by-zero
-        } else {
-            self.ticks_per_rev as f32
+    /// Command every servo to its present position, read fresh from the bus.
+    ///
+    /// Servos that fail to answer are left alone rather than sent a stale
+    /// goal.  Bypasses smoothing, and re-seeds each smoother from the present
+    /// position so the next accepted goal is smoothed from where the arm stopped.
+    fn hold_present_positions(&mut self) -> CuResult<()> {
+        let mut entries: HeaplessVec<(u8, u16), MAX_SERVOS> = HeaplessVec::new();
+        for i in 0..self.num_servos as usize {
+            let raw = match self.read_present_position(self.ids[i]) {
+                Ok(raw) => raw,
+                Err(e) => {
+                    debug!(
+                        "FeetechBridge: cannot hold servo {}, position read failed: {}",
+                        self.ids[i], e
+                    );
+                    continue;
+                }
+            };
+            self.cache_position(i, raw);
+            let present = self.input_units.from_raw(
+                raw,
+                self.centers[i],
+                self.param_for(self.input_units, i),
+            );
+            if !self.smoothers[i].is_bypass() {
+                self.smoothers[i].seed(present);
+            }
+            let _ = entries.push((self.ids[i], raw));
         }
+        self.sync_write_raw(&entries)
     }
 
-    /// Enable torque on every configured servo.
-    fn enable_all_torque(&mut self) -> CuResult<()> {
-        for i in 0..self.num_servos as usize {
-            self.set_torque(self.ids[i], true).map_err(|e| {
-                CuError::new_with_cause(
-                    &format!("Feetech: failed to enable torque on servo {}", self.ids[i]),
-                    e,
-                )
-            })?;
+    /// Sync-write raw goal positions for the given `(id, raw)` pairs only.
+    ///
+    /// Servos skipped because of a `"skip_write_on"` error flag are left out.
+    fn sync_write_raw(&mut self, entries: &[(u8, u16)]) -> CuResult<()> {
+        let entries: HeaplessVec<(u8, u16), MAX_SERVOS> = entries
+            .iter()
+            .filter(|(id, _)| !self.slot_of(*id).is_some_and(|i| self.skipping[i]))
+            .copied()
+            .collect();
+        if entries.is_empty() {
+            return Ok(());
+        }
+        match self.goal_write {
+            GoalWrite::Sync => {
+                let mut params = [0u8; MAX_PACKET_SIZE - 5];
+                let params_size = build_goal_sync_write(&entries, &mut params)?;
+                self.send_packet(BROADCAST_ID, instr::SYNC_WRITE, &params[..params_size])
+                    .map_err(|e| CuError::new_with_cause("Feetech: sync-write failed", e))?;
+                if self.verify_sync_write {
+                    self.rewrite_missed_goals(&entries);
+                }
+            }
+            GoalWrite::RegWrite => self.reg_write_goals(&entries)?,
+        }
+        for &(id, raw) in &entries {
+            if let Some(i) = self.slot_of(id) {
+                self.stuck[i].goal = Some(raw);
+            }
         }
         Ok(())
     }
-}
-
-// ===========================================================================
-// CuBridge trait implementation
-// ===========================================================================
-
-impl CuBridge for FeetechBridge {
-    type Tx = TxChannels;
-    type Rx = RxChannels;
-    type Resources<'r> = Resources;
 
-    /// Construct the bridge from configuration.
-    ///
-    /// Expected `copperconfig.ron` keys under the bridge's `config` block:
-    ///
-    /// | Key                | Type   | Description                                   |
-    /// |--------------------|--------|-----------------------------------------------|
-    /// | `servo0`           | u8     | Bus ID of the first servo                     |
-    /// | `servo1`           | u8     | Bus ID of the second servo                    |
-    /// | …                  | …      | Up to `servo7`                                |
-    /// | `units`            | string | `"raw"` (default), `"deg"`, `"rad"`, or `"normalize"` |
-    /// | `calibration_file` | string | Path to calibration JSON (required for deg/rad/normalize) |
-    /// | `ticks_per_rev`    | integer | Raw units per 360° (model-dependent; default 4096) |
+    /// Read back each servo's goal after a sync-write and write it again,
+    /// individually, to the servos that missed the broadcast.
     ///
-    /// At least `servo0` must be present.
-    fn new(
-        config: Option<&ComponentConfig>,
-        tx_channels: &[BridgeChannelConfig<<Self::Tx as BridgeChannelSet>::Id>],
-        _rx_channels: &[BridgeChannelConfig<<Self::Rx as BridgeChannelSet>::Id>],
-        resources: Self::Resources<'_>,
-    ) -> CuResult<Self>
-    where
-        Self: Sized,
-    {
-        let cfg = config.ok_or("FeetechBridge requires a config block with servo IDs")?;
+    /// A servo whose goal cannot be read back is re-written too.
+    fn rewrite_missed_goals(&mut self, entries: &[(u8, u16)]) {
+        for &(id, raw) in entries {
+            let latched = self
+                .read_register(id, reg::GOAL_POSITION, 2)
+                .is_ok_and(|data| data.len() >= 2 && u16::from_le_bytes([data[0], data[1]]) == raw);
+            if latched {
+                continue;
+            }
+            debug!(
+                "FeetechBridge: servo {} missed the sync-write, re-writing goal {}",
+                id, raw
+            );
+            if let Err(e) = self.write_register(id, reg::GOAL_POSITION, &raw.to_le_bytes()) {
+                warning!(
+                    "FeetechBridge: failed to re-write goal to servo {}: {}",
+                    id,
+                    e.to_string()
+                );
+            }
+        }
+    }
 
-        // Collect servo IDs from sequential keys: "servo0", "servo1", …
-        // Stop at the first missing key (after servo0, which is mandatory).
-        let mut ids = [0u8; MAX_SERVOS];
-        let mut num_servos: u8 = 0;
-        for (i, id_slot) in ids.iter_mut().enumerate().take(MAX_SERVOS) {
-            let key = format!("servo{}", i);
-            match cfg.get::<u8>(&key)? {
-                Some(id) => {
-                    *id_slot = id;
-                    num_servos = (i + 1) as u8;
-                }
-                None if i == 0 => {
-                    return Err(
-                        "FeetechBridge: you must configure at least one servo ID (\"servo0\")"
-                            .into(),
+    /// Buffer each goal with REG_WRITE, then start them together with ACTION.
+    ///
+    /// A servo that stops acknowledging its REG_WRITE is warned about once,
+    /// and again only after it answered in between.  With
+    /// `"verify_reg_write"` set, every servo's async-write flag is read back
+    /// first; a servo that did not latch its goal is logged, and with
+    /// `"abort"` ACTION is not sent so no servo starts a partial move.
+    fn reg_write_goals(&mut self, entries: &[(u8, u16)]) -> CuResult<()> {
+        for &(id, raw) in entries {
+            let [lo, hi] = raw.to_le_bytes();
+            self.send_packet(id, instr::REG_WRITE, &[reg::GOAL_POSITION, lo, hi])
+                .map_err(|e| {
+                    CuError::new_with_cause(&format!("Feetech: reg-write to servo {id} failed"), e)
+                })?;
+            let acked = self.read_status_packet();
+            let Some(i) = self.slot_of(id) else {
+                continue;
+            };
+            match acked {
+                Err(e) if !self.reg_write_unacked[i] => {
+                    self.reg_write_unacked[i] = true;
+                    warning!(
+                        "FeetechBridge: servo {} did not acknowledge its reg-write goal: {}",
+                        id,
+                        e.to_string()
                     );
                 }
-                None => break, // no more servos configured
+                Ok(_) if self.reg_write_unacked[i] => {
+                    self.reg_write_unacked[i] = false;
+                    info!("FeetechBridge: servo {} acknowledges reg-writes again", id);
+                }
+                _ => {}
+            }
+        }
+        if self.verify_reg_write != VerifyRegWrite::Off {
+            let unlatched: HeaplessVec<u8, MAX_SERVOS> = entries
+                .iter()
+                .map(|&(id, _)| id)
+                .filter(|&id| {
+                    !self
+                        .read_register(id, reg::ASYNC_WRITE_FLAG, 1)
+                        .is_ok_and(|data| data.first() == Some(&1))
+                })
+                .collect();
+            if !unlatched.is_empty() {
+                let msg = format!("servos {unlatched:?} did not latch their goal");
+                if self.verify_reg_write == VerifyRegWrite::Abort {
+                    return Err(format!("FeetechBridge: {msg}, ACTION aborted").into());
+                }
+                warning!("FeetechBridge: {}, sending ACTION anyway", msg);
             }
         }
+        self.send_packet(BROADCAST_ID, instr::ACTION, &[])
+            .map_err(|e| CuError::new_with_cause("Feetech: action failed", e))
+    }
 
-        // ---- Parse output units ----
-        let units = match cfg.get::<String>("units")? {
-            Some(s) => s.parse().map_err(|_| {
-                CuError::from(format!(
-                    "FeetechBridge: unknown units \"{s}\". Use \"raw\", \"deg\", \"rad\", or \"normalize\"."
-                ))
-            })?,
-            None => Units::Raw,
-        };
+    /// Store a fresh position reading of servo slot `i`.
+    fn cache_position(&mut self, i: usize, raw: u16) {
+        self.cached_positions[i] = raw;
+        self.position_known[i] = true;
+    }
 
-        // ---- Load calibration (required for deg / rad / normalize) ----
-        let mut centers = [0.0f32; MAX_SERVOS];
-        let mut half_ranges = [0.0f32; MAX_SERVOS];
-        if units != Units::Raw {
-            let cal_path = cfg
-                .get::<String>("calibration_file")?
-                .ok_or("FeetechBridge: \"calibration_file\" is required when units != raw")?;
-            let cal = CalibrationData::load(std::path::Path::new(&cal_path)).map_err(|e| {
-                CuError::new_with_cause(
-                    &format!("FeetechBridge: failed to load calibration from \"{cal_path}\""),
-                    e,
-                )
-            })?;
-            for i in 0..num_servos as usize {
-                centers[i] = cal.center_for(ids[i]).ok_or_else(|| {
-                    CuError::from(format!(
-                        "FeetechBridge: no calibration entry for servo ID {} in \"{cal_path}\"",
-                        ids[i]
-                    ))
-                })?;
-                if units == Units::Normalize {
-                    half_ranges[i] = cal.half_range_for(ids[i]).ok_or_else(|| {
-                        CuError::from(format!(
-                            "FeetechBridge: no calibration entry for servo ID {} in \"{cal_path}\" (normalize)",
-                            ids[i]
-                        ))
-                    })?;
-                }
+    /// Read every servo's present position once, so wrapped goals have a
+    /// reference even when no Rx channel polls the bus.
+    fn read_unwrap_references(&mut self) {
+        for i in 0..self.num_servos as usize {
+            match self.read_present_position(self.ids[i]) {
+                Ok(raw) => self.cache_position(i, raw),
+                Err(e) => debug!(
+                    "FeetechBridge: no unwrap reference for servo {}: {}",
+                    self.ids[i], e
+                ),
             }
         }
+    }
 
-        // ---- Ticks per revolution (model-dependent; used for deg/rad) ----
-        let ticks_per_rev = cfg.get::<u32>("ticks_per_rev")?.unwrap_or(4096);
+    /// Slot index of the servo with bus ID `id`, if configured.
+    fn slot_of(&self, id: u8) -> Option<usize> {
+        self.ids[..self.num_servos as usize]
+            .iter()
+            .position(|&s| s == id)
+    }
 
-        let port = resources.serial.0;
+    /// Run the stuck detector on a fresh reading of slot `i`, logging transitions.
+    fn observe_stuck(&mut self, i: usize, raw: u16) {
+        let Some(detector) = self.stuck_detector else {
+            return;
+        };
+        let was_stuck = self.stuck[i].stuck;
+        let stuck = detector.observe(&mut self.stuck[i], raw);
+        if stuck && !was_stuck {
+            warning!(
+                "FeetechBridge: servo {} position stuck at {} for {} cycles (goal {})",
+                self.ids[i],
+                raw,
+                self.stuck[i].unchanged,
+                self.stuck[i].goal.unwrap_or(raw)
+            );
+            self.dump_flight_recorder(&format!("servo {} position stuck", self.ids[i]));
+        } else if !stuck && was_stuck {
+            info!("FeetechBridge: servo {} position moving again", self.ids[i]);
+        }
+    }
 
-        // If no Tx channels are wired up in this mission, nobody will send
-        // goal positions → the arm is in read-only (follower / teach) mode.
-        let has_writers = !tx_channels.is_empty();
+    /// Add this cycle's reads to the flight recorder and dump it when a servo
+    /// just crossed [`DEAD_SERVO_READS`] consecutive failures or started
+    /// reporting an overload.
+    fn record_cycle(&mut self, failed: [bool; MAX_SERVOS]) {
+        let Some(recorder) = &mut self.recorder else {
+            return;
+        };
+        recorder.record(RecorderEntry {
+            seq: self.cycle_seq,
+            time: self.last_read_time,
+            raw: self.cached_positions,
+            failed,
+        });
+        let mut faults = Vec::new();
+        for (i, &failed) in failed.iter().enumerate().take(self.num_servos as usize) {
+            // Equality, not >=: a servo that stays dead is reported once.
+            if recorder.consecutive_failures(i) == DEAD_SERVO_READS {
+                faults.push(format!("servo {} stopped answering", self.ids[i]));
+            }
+            // A servo that did not answer has no fresh error byte.
+            let overloaded = self.servo_errors[i].intersects(ServoError::OVERLOAD);
+            if !failed && recorder.observe_overload(i, overloaded) {
+                faults.push(format!("servo {} overloaded", self.ids[i]));
+            }
+        }
+        for fault in faults {
+            self.dump_flight_recorder(&fault);
+        }
+    }
 
-        Ok(FeetechBridge {
-            port,
-            ids,
-            num_servos,
-            has_writers,
-            cached_positions: [0u16; MAX_SERVOS],
-            units,
-            centers,
-            ticks_per_rev,
-            half_ranges,
-        })
+    /// Append the flight recorder to its file, if enabled.
+    fn dump_flight_recorder(&self, reason: &str) {
+        let Some(recorder) = &self.recorder else {
+            return;
+        };
+        let ids = &self.ids[..self.num_servos as usize];
+        match recorder.dump(&self.recorder_file, ids, reason) {
+            Ok(()) => warning!(
+                "FeetechBridge: {}, flight recorder dumped to {}",
+                reason.to_string(),
+                self.recorder_file.display().to_string()
+            ),
+            Err(e) => error!(
+                "FeetechBridge: {}, failed to dump flight recorder: {}",
+                reason.to_string(),
+                e.to_string()
+            ),
+        }
     }
 
-    /// Called once before the first processing cycle.
+    /// Calibration refined while running, when `"auto_calibrate"` is enabled.
+    pub fn auto_calibration(&self) -> Option<&CalibrationData> {
+        self.auto_calibration.as_ref().map(AutoCalibration::data)
+    }
+
+    /// The conversion setup in use, for saving and loading into another bridge.
     ///
-    /// Enables torque only when writers are connected (commander mode).
-    /// In follower mode torque stays off so the arm moves freely.
-    fn start(&mut self, _ctx: &CuContext) -> CuResult<()> {
-        if self.has_writers {
-            self.enable_all_torque()?;
-            debug!(
-                "FeetechBridge: enabled torque on {} servos",
-                self.num_servos
-            );
-        } else {
-            debug!(
-                "FeetechBridge: read-only mode, torque left disabled on {} servos",
-                self.num_servos
-            );
+    /// Calibration is exported as loaded: center shifts applied by
+    /// `"home_mismatch"` are re-measured at each start and not included.
+    pub fn export_config(&self) -> ConversionConfig {
+        ConversionConfig {
+            version: CONVERSION_FORMAT_VERSION,
+            output_units: self.output_units,
+            input_units: self.input_units,
+            velocity_units: self.velocity_units,
+            ticks_per_rev: self.ticks_per_rev,
+            wrap_angles: self.wrap_angles,
+            servos: self.calibration.servos.clone(),
         }
-        Ok(())
     }
 
-    /// Handle an outgoing message on a Tx channel.
+    /// Replace the conversion setup with `config`, after validating it
+    /// against the configured servos and the home settings that depend on
+    /// calibrated units (`"home_mismatch": "offset"`, `homing_reference<i>`).
+    /// Nothing changes if it is invalid.
     ///
-    /// For `goal_positions`: sync-writes the raw positions to the servo bus.
-    fn send<'a, Payload>(
-        &mut self,
-        _ctx: &CuContext,
-        channel: &'static BridgeChannel<<Self::Tx as BridgeChannelSet>::Id, Payload>,
-        msg: &CuMsg<Payload>,
-    ) -> CuResult<()>
-    where
-        Payload: CuMsgPayload + 'a,
-    {
-        match channel.id() {
-            TxId::GoalPositions => {
-                let goal_msg: &CuMsg<JointPositions> = msg.downcast_ref()?;
-                if let Some(positions) = goal_msg.payload() {
-                    self.sync_write_positions(positions)?;
-                }
+    /// Goal smoothers are reset, since their state is in the old input unit.
+    pub fn import_config(&mut self, config: ConversionConfig) -> CuResult<()> {
+        let ids = self.ids;
+        let ids = &ids[..self.num_servos as usize];
+        config
+            .validate(ids)
+            .map_err(|e| CuError::from(format!("FeetechBridge: invalid conversion config: {e}")))?;
+        if !needs_calibration(
+            config.output_units,
+            config.input_units,
+            config.velocity_units,
+        ) {
+            if self.home_mismatch == HomeMismatch::Offset {
+                return Err("FeetechBridge: invalid conversion config: home_mismatch \"offset\" requires units other than raw".into());
             }
+            if let Some(i) = self.homing[..self.num_servos as usize]
+                .iter()
+                .position(|h| h.is_some_and(|h| h.reference.is_some()))
+            {
+                return Err(format!("FeetechBridge: invalid conversion config: homing_reference{i} requires units other than raw").into());
+            }
+        }
+        let mut servos = Vec::with_capacity(ids.len());
+        for (i, &id) in ids.iter().enumerate() {
+            let cal = config.servos.iter().find(|s| s.id == id);
+            self.centers[i] = cal.map_or(0.0, ServoCalibration::center);
+            self.half_ranges[i] = cal.map_or(0.0, |s| s.range() as f32 / 2.0);
+            servos.extend(cal.cloned());
+        }
+        self.output_units = config.output_units;
+        self.input_units = config.input_units;
+        self.velocity_units = config.velocity_units;
+        self.ticks_per_rev = config.ticks_per_rev;
+        self.wrap_angles = config.wrap_angles;
+        self.calibration = CalibrationData { servos };
+        for smoother in &mut self.smoothers {
+            smoother.reset();
         }
         Ok(())
     }
 
-    /// Produce an incoming message on an Rx channel.
-    ///
-    /// For `positions`: reads every servo's present position and publishes
-    /// them as a [`JointPositions`].
-    fn receive<'a, Payload>(
-        &mut self,
-        ctx: &CuContext,
-        channel: &'static BridgeChannel<<Self::Rx as BridgeChannelSet>::Id, Payload>,
-        msg: &mut CuMsg<Payload>,
-    ) -> CuResult<()>
-    where
-        Payload: CuMsgPayload + 'a,
-    {
-        // Poll all servos and update the cache.
-        self.read_all_positions()?;
-
-        // Stamp the message with the current robot time.
-        msg.tov = Tov::Time(ctx.now());
+    /// Health of every configured servo, indexed by slot.
+    pub fn health(&self) -> HeaplessVec<ServoHealth, MAX_SERVOS> {
+        (0..self.num_servos as usize)
+            .map(|i| ServoHealth {
+                id: self.ids[i],
+                stuck: self.stuck[i].stuck,
+                errors: self.servo_errors[i],
+                skipped: self.skipping[i],
+                diagnostics: self.diagnostics[i],
+            })
+            .collect()
+    }
 
-        match channel.id() {
-            RxId::Positions => {
-                // Build the payload, converting each raw position to the
-                // configured output unit (raw / deg / rad).
-                let mut payload = JointPositions::new();
-                payload.fill_from_iter(
-                    self.cached_positions[..self.num_servos as usize]
-                        .iter()
-                        .enumerate()
-                        .map(|(i, &raw)| {
-                            self.units
-                                .from_raw(raw, self.centers[i], self.param_for_slot(i))
-                        }),
+    /// Skip or resume goal writes per servo from its latest error flags.
+    fn update_write_skips(&mut self) {
+        if self.skip_write_on.is_empty() {
+            return;
+        }
+        for i in 0..self.num_servos as usize {
+            let errors = self.servo_errors[i];
+            let skip = errors.intersects(self.skip_write_on);
+            if skip && !self.skipping[i] {
+                warning!(
+                    "FeetechBridge: servo {} reports {}, skipping its goal writes",
+                    self.ids[i],
+                    errors.names()
+                );
+            } else if !skip && self.skipping[i] {
+                info!(
+                    "FeetechBridge: servo {} error cleared, resuming goal writes",
+                    self.ids[i]
                 );
-                let pos_msg: &mut CuMsg<JointPositions> = msg.downcast_mut()?;
-                pos_msg.set_payload(payload);
             }
+            self.skipping[i] = skip;
         }
-        Ok(())
     }
 
-    /// Called once after the last processing cycle.
+    /// Convert a goal in the configured unit to a raw tick for servo slot `i`.
     ///
-    /// Disables torque on every servo for safety (prevents the arm from
-    /// holding position with power applied after the application exits).
-    fn stop(&mut self, _ctx: &CuContext) -> CuResult<()> {
-        for i in 0..self.num_servos as usize {
-            if let Err(e) = self.set_torque(self.ids[i], false) {
-                debug!(
-                    "FeetechBridge: failed to disable torque on servo {}: {}",
-                    self.ids[i],
-                    e.to_string()
-                );
+    /// Unwraps the goal if needed, runs it through the joint's smoother and
+    /// records its quantization error when enabled.  A wrapped goal is refused
+    /// until the servo's present position has been read at least once, and so
+    /// is a smoothed goal whose filter cannot be seeded.
+    fn goal_to_raw(&mut self, i: usize, value: f32) -> CuResult<u16> {
+        let units = self.input_units;
+        let param = self.param_for(units, i);
+        let mut value = value;
+        if self.wrap_angles && units.full_turn().is_some() {
+            if !self.position_known[i] {
+                return Err(format!(
+                    "FeetechBridge: cannot unwrap goal for servo {}, its present position was never read",
+                    self.ids[i]
+                )
+                .into());
             }
+            // Resolve the wrapped goal against the last present position.
+            let reference = units.from_raw(self.cached_positions[i], self.centers[i], param);
+            value = units.unwrap_near(value, reference);
         }
-        debug!(
-            "FeetechBridge: disabled torque on {} servos",
-            self.num_servos
-        );
-        Ok(())
+        if self.smoothers[i].needs_seed() {
+            let present = self.seed_position(i)?;
+            self.smoothers[i].seed(present);
+        }
+        let value = self.smoothers[i].apply(value);
+        let raw = units.to_raw(value, self.centers[i], param);
+        self.record_quantization(i, value);
+        Ok(raw)
     }
-}
 
-// ===========================================================================
-// Tests
-// ===========================================================================
+    /// Present position of slot `i` in the input unit, to seed its smoother.
+    ///
+    /// Read fresh from the bus, falling back to the last reading.
+    fn seed_position(&mut self, i: usize) -> CuResult<f32> {
+        match self.read_present_position(self.ids[i]) {
+            Ok(raw) => self.cache_position(i, raw),
+            Err(e) if self.position_known[i] => debug!(
+                "FeetechBridge: seeding servo {} smoother from its last reading: {}",
+                self.ids[i], e
+            ),
+            Err(e) => {
+                return Err(CuError::new_with_cause(
+                    &format!(
+                        "FeetechBridge: cannot smooth goal for servo {}, its present position is unknown",
+                        self.ids[i]
+                    ),
+                    e,
+                ));
+            }
+        }
+        let units = self.input_units;
+        Ok(units.from_raw(
+            self.cached_positions[i],
+            self.centers[i],
+            self.param_for(units, i),
+        ))
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Record the rounding loss of commanding `value` on slot `i`.
+    fn record_quantization(&mut self, i: usize, value: f32) {
+        if self.quantization.is_none() {
+            return;
+        }
+        let units = self.input_units;
+        let error = units.quantization_error(value, self.centers[i], self.param_for(units, i));
+        if let Some(stats) = self.quantization.as_mut() {
+            stats[i].record(error);
+        }
+    }
 
-    #[test]
-    fn checksum_matches_known_values() {
-        // Hand-calculated example:
-        //   ID=1, Length=4, Instruction=WRITE(3), Addr=40, Data=1
-        //   body = [0x01, 0x04, 0x03, 0x28, 0x01]
-        //   sum  = 1+4+3+40+1 = 49 = 0x31
+    /// Accumulated quantization error per servo slot, if `"log_quantization"` is set.
+    pub fn quantization_stats(&self) -> Option<&[QuantizationStats]> {
+        self.quantization
+            .as_ref()
+            .map(|stats| &stats[..self.num_servos as usize])
+    }
+
+    /// Last read present positions of every servo, in the configured unit.
+    fn present_payload(&self) -> JointPositions {
+        let mut payload = JointPositions::new();
+        payload.fill_from_iter((0..self.num_servos as usize).map(|i| self.present_value(i)));
+        payload
+    }
+
+    /// Last read speeds of every servo, in `velocity_units` per second.
+    fn velocity_payload(&self) -> JointVelocities {
+        let mut out = JointVelocities::new();
+        let units = self.velocity_units;
+        out.fill_from_iter(
+            (0..self.num_servos as usize).map(|i| {
+                units.rate_from_raw(self.cached_speeds[i] as f32, self.param_for(units, i))
+            }),
+        );
+        out
+    }
+
+    /// Last read present position of servo slot `i`, in the configured unit.
+    fn present_value(&self, i: usize) -> f32 {
+        let units = self.output_units;
+        let value = units.from_raw(
+            self.cached_positions[i],
+            self.centers[i],
+            self.param_for(units, i),
+        );
+        if self.wrap_angles {
+            units.wrap(value)
+        } else {
+            value
+        }
+    }
+
+    // =======================================================================
+    // Servo groups
+    // =======================================================================
+
+    /// Servo groups declared in the `"groups"` config key.
+    pub fn groups(&self) -> &ServoGroups {
+        &self.groups
+    }
+
+    /// Last read positions of the servos in group `name`, in group order and
+    /// in the configured unit.
+    pub fn group_positions(&self, name: &str) -> CuResult<JointPositions> {
+        let group = self.group(name)?;
+        let mut positions = JointPositions::new();
+        positions.fill_from_iter(group.slots.iter().map(|&i| self.present_value(i as usize)));
+        Ok(positions)
+    }
+
+    /// Command the servos in group `name` only.
+    ///
+    /// `positions` are in the configured unit and in group order; their count
+    /// must match the group size.  Servos outside the group are not written.
+    ///
+    /// The goals are queued like a `goal_positions` message and written in
+    /// [`postprocess`](CuBridge::postprocess), through the same per-cycle
+    /// command priority (see [`commands`]) and conversion path: an e-stop in
+    /// the same cycle drops them, smoothing and `wrap_angles` apply, and
+    /// servos in the `skip_write_on` set are left out.  The call is refused
+    /// while the e-stop is latched.  Only the last goal queued in a cycle is
+    /// applied, whether it came from here or from `goal_positions`.
+    pub fn write_group_positions(&mut self, name: &str, positions: &[f32]) -> CuResult<()> {
+        if self.estopped {
+            return Err(format!(
+                "FeetechBridge: refusing to move servo group \"{name}\" while e-stopped"
+            )
+            .into());
+        }
+        let group = self.group(name)?;
+        if positions.len() != group.slots.len() {
+            return Err(format!(
+                "FeetechBridge: servo group \"{name}\" has {} servos but got {} positions",
+                group.slots.len(),
+                positions.len()
+            )
+            .into());
+        }
+        let slots = group.slots.clone();
+        let mut values = JointPositions::new();
+        values.fill_from_iter(positions.iter().copied());
+        self.pending.goal = Some(GoalCommand::Group {
+            slots,
+            positions: values,
+        });
+        Ok(())
+    }
+
+    fn group(&self, name: &str) -> CuResult<&ServoGroup> {
+        self.groups
+            .get(name)
+            .ok_or_else(|| CuError::from(format!("FeetechBridge: unknown servo group \"{name}\"")))
+    }
+
+    /// Enable or disable torque on a single servo.
+    ///
+    /// When torque is **enabled** the servo actively holds its position.
+    /// When **disabled** the servo can be moved freely by hand.
+    #[allow(dead_code)]
+    pub fn set_torque(&mut self, id: u8, enable: bool) -> io::Result<()> {
+        self.write_register(id, reg::TORQUE_ENABLE, &[enable as u8])
+    }
+
+    /// Parameter for from_raw/to_raw: ticks_per_rev for Deg/Rad, half_ranges[i] for Normalize.
+    #[inline]
+    fn param_for(&self, units: Units, i: usize) -> f32 {
+        if units == Units::Normalize {
+            let hr = self.half_ranges[i];
+            if hr > 0.0 { hr } else { 1.0 } // avoid div-by-zero
+        } else {
+            self.ticks_per_rev as f32
+        }
+    }
+
+    /// Compare each servo's present position with its expected home.
+    ///
+    /// Servos without an expected home, or that fail to answer, are skipped.
+    fn check_home(&mut self) {
+        for i in 0..self.num_servos as usize {
+            let Some(expected) = self.expected_home[i] else {
+                continue;
+            };
+            let present = match self.read_present_position(self.ids[i]) {
+                Ok(raw) => raw,
+                Err(e) => {
+                    debug!(
+                        "FeetechBridge: home check skipped for servo {}: {}",
+                        self.ids[i], e
+                    );
+                    continue;
+                }
+            };
+            self.cache_position(i, present);
+            let Some(delta) = home_deviation(present, expected, self.home_tolerance) else {
+                continue;
+            };
+            match self.home_mismatch {
+                HomeMismatch::Warn => {
+                    warning!(
+                        "FeetechBridge: servo {} starts at {} but home is {} ({} ticks off); was it turned while unpowered?",
+                        self.ids[i],
+                        present,
+                        expected,
+                        delta
+                    );
+                }
+                HomeMismatch::Offset => {
+                    warning!(
+                        "FeetechBridge: servo {} starts {} ticks away from home {}, offsetting its center",
+                        self.ids[i],
+                        delta,
+                        expected
+                    );
+                    self.centers[i] += delta as f32;
+                }
+            }
+        }
+    }
+
+    /// Read load, voltage and temperature of one servo in a single request.
+    fn read_diagnostics(&mut self, id: u8) -> CuResult<ServoDiagnostics> {
+        // PRESENT_LOAD (2), PRESENT_VOLTAGE (1), PRESENT_TEMPERATURE (1).
+        let data = self.read_register(id, reg::PRESENT_LOAD, 4).map_err(|e| {
+            CuError::new_with_cause(
+                &format!("Feetech: failed to read diagnostics from servo {}", id),
+                e,
+            )
+        })?;
+        if data.len() < 4 {
+            return Err(format!(
+                "Feetech: short read for diagnostics from servo {} (got {} bytes)",
+                id,
+                data.len()
+            )
+            .into());
+        }
+        Ok(ServoDiagnostics {
+            load: decode_load(u16::from_le_bytes([data[0], data[1]])),
+            voltage: data[2],
+            temperature: data[3],
+        })
+    }
+
+    /// Read diagnostics from every servo if this cycle is on the configured phase.
+    fn poll_diagnostics(&mut self) {
+        if !diagnostics_due(
+            self.cycle_seq,
+            self.diagnostics_interval,
+            self.diagnostics_phase,
+        ) {
+            return;
+        }
+        for i in 0..self.num_servos as usize {
+            match self.read_diagnostics(self.ids[i]) {
+                Ok(diag) => self.diagnostics[i] = Some(diag),
+                Err(e) => debug!(
+                    "FeetechBridge: failed to read diagnostics from servo {}: {}",
+                    self.ids[i], e
+                ),
+            }
+        }
+    }
+
+    /// Read the signed present load of one servo (see [`decode_load`]).
+    fn read_present_load(&mut self, id: u8) -> CuResult<i16> {
+        let data = self.read_register(id, reg::PRESENT_LOAD, 2).map_err(|e| {
+            CuError::new_with_cause(
+                &format!("Feetech: failed to read load from servo {}", id),
+                e,
+            )
+        })?;
+        if data.len() < 2 {
+            return Err(format!(
+                "Feetech: short read for load from servo {} (got {} bytes)",
+                id,
+                data.len()
+            )
+            .into());
+        }
+        Ok(decode_load(u16::from_le_bytes([data[0], data[1]])))
+    }
+
+    /// Read a 2-byte little-endian register, wrapping errors with context.
+    fn read_u16(&mut self, id: u8, address: u8, what: &str) -> CuResult<u16> {
+        let data = self.read_register(id, address, 2).map_err(|e| {
+            CuError::new_with_cause(
+                &format!("Feetech: failed to read {} from servo {}", what, id),
+                e,
+            )
+        })?;
+        if data.len() < 2 {
+            return Err(format!(
+                "Feetech: short read for {} from servo {} (got {} bytes)",
+                what,
+                id,
+                data.len()
+            )
+            .into());
+        }
+        Ok(u16::from_le_bytes([data[0], data[1]]))
+    }
+
+    /// Write a 2-byte little-endian register, wrapping errors with context.
+    fn write_u16(&mut self, id: u8, address: u8, value: u16, what: &str) -> CuResult<()> {
+        self.write_register(id, address, &value.to_le_bytes())
+            .map_err(|e| {
+                CuError::new_with_cause(
+                    &format!("Feetech: failed to write {} on servo {}", what, id),
+                    e,
+                )
+            })
+    }
+
+    /// Drive servo slot `i` toward its stop until it stalls.
+    ///
+    /// Returns the raw position where the stall was detected.  The torque
+    /// limit is lowered to `cfg.torque_limit` for the search.  Whatever
+    /// happens, the servo is then left holding where it stopped (or with
+    /// torque off if that position cannot be read), with the speed limit
+    /// cleared and its previous torque limit restored.
+    fn home_servo(&mut self, ctx: &CuContext, i: usize, cfg: HomingConfig) -> CuResult<u16> {
+        let id = self.ids[i];
+        let target = match cfg.direction {
+            HomingDirection::Min => 0,
+            HomingDirection::Max => {
+                self.ticks_per_rev.saturating_sub(1).min(u16::MAX as u32) as u16
+            }
+        };
+        let torque_limit = self.read_u16(id, reg::TORQUE_LIMIT, "torque limit")?;
+        self.set_torque(id, true).map_err(|e| {
+            CuError::new_with_cause(
+                &format!(
+                    "Feetech: failed to enable torque for homing on servo {}",
+                    id
+                ),
+                e,
+            )
+        })?;
+        let result = self.drive_to_stop(ctx, id, target, cfg);
+
+        // Stop pushing against the stop and restore the limits, whatever happened.
+        let stop = match &result {
+            Ok(stop) => Some(*stop),
+            Err(_) => self.read_present_position(id).ok(),
+        };
+        let hold = match stop {
+            Some(stop) => self.write_u16(id, reg::GOAL_POSITION, stop, "homing hold"),
+            None => self.set_torque(id, false).map_err(|e| {
+                CuError::new_with_cause(
+                    &format!("Feetech: failed to disable torque after homing on servo {id}"),
+                    e,
+                )
+            }),
+        };
+        let speed = self.write_u16(id, reg::GOAL_SPEED, 0, "speed limit");
+        let limit = self.write_u16(id, reg::TORQUE_LIMIT, torque_limit, "torque limit");
+        let restored = hold.and(speed).and(limit);
+        if result.is_err()
+            && let Err(e) = &restored
+        {
+            warning!(
+                "FeetechBridge: failed to restore servo {} after homing: {}",
+                id,
+                e.to_string()
+            );
+        }
+        result.and_then(|stop| restored.map(|()| stop))
+    }
+
+    /// Command servo `id` toward `target` at the homing speed and torque
+    /// limit, and poll its load until it stalls or the timeout runs out.
+    fn drive_to_stop(
+        &mut self,
+        ctx: &CuContext,
+        id: u8,
+        target: u16,
+        cfg: HomingConfig,
+    ) -> CuResult<u16> {
+        self.write_u16(id, reg::GOAL_SPEED, cfg.speed, "homing speed")?;
+        self.write_u16(
+            id,
+            reg::TORQUE_LIMIT,
+            cfg.torque_limit,
+            "homing torque limit",
+        )?;
+        self.write_u16(id, reg::GOAL_POSITION, target, "homing goal")?;
+
+        let mut detector = StallDetector::new(cfg.load_threshold, HOMING_CONFIRM_SAMPLES);
+        let deadline = ctx.now() + self.homing_timeout;
+        loop {
+            // Transient read errors just cost a sample.
+            if let Ok(load) = self.read_present_load(id)
+                && detector.observe(load)
+            {
+                return self.read_present_position(id);
+            }
+            if ctx.now() > deadline {
+                return Err(format!(
+                    "Feetech: servo {} did not reach a hard stop within {} ms",
+                    id,
+                    self.homing_timeout.as_millis()
+                )
+                .into());
+            }
+            std::thread::sleep(std::time::Duration::from_millis(HOMING_POLL_INTERVAL_MS));
+        }
+    }
+
+    /// Home every servo that has a homing direction configured.
+    fn run_homing(&mut self, ctx: &CuContext) -> CuResult<()> {
+        for i in 0..self.num_servos as usize {
+            let Some(cfg) = self.homing[i] else {
+                continue;
+            };
+            let stop = self.home_servo(ctx, i, cfg)?;
+            self.cache_position(i, stop);
+            self.homed_positions[i] = Some(stop);
+            match cfg.reference {
+                Some(reference) => {
+                    let delta = stop as i32 - reference as i32;
+                    self.centers[i] += delta as f32;
+                    info!(
+                        "FeetechBridge: servo {} homed at {} ({} ticks from reference {})",
+                        self.ids[i], stop, delta, reference
+                    );
+                }
+                None => {
+                    info!("FeetechBridge: servo {} homed at {}", self.ids[i], stop);
+                }
+            }
+            // Follower mode keeps torque off once homing is done.
+            if !self.has_writers
+                && let Err(e) = self.set_torque(self.ids[i], false)
+            {
+                warning!(
+                    "FeetechBridge: failed to disable torque on servo {} after homing: {}",
+                    self.ids[i],
+                    e.to_string()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Raw positions recorded by hard-stop homing, per servo slot.
+    pub fn homed_positions(&self) -> &[Option<u16>] {
+        &self.homed_positions[..self.num_servos as usize]
+    }
+
+    /// Jog every servo forward and back and check that it followed.
+    ///
+    /// Each servo is moved `jog.ticks` up, then `jog.ticks` down from its
+    /// present position at `jog.speed`, and returned to where it started.
+    /// Servos that fail are reported, not treated as errors; only bus write
+    /// failures abort the check.
+    pub fn self_check(&mut self, ctx: &CuContext) -> CuResult<&[JogResult]> {
+        self.self_check_results.clear();
+        for i in 0..self.num_servos as usize {
+            let result = self.jog_servo(ctx, i)?;
+            if result.passed() {
+                info!(
+                    "FeetechBridge: servo {} passed the jog self-check",
+                    result.id
+                );
+            } else {
+                warning!(
+                    "FeetechBridge: servo {} failed the jog self-check (forward {}, backward {})",
+                    result.id,
+                    result.forward,
+                    result.backward
+                );
+            }
+            self.self_check_results.push(result);
+        }
+        Ok(&self.self_check_results)
+    }
+
+    /// Report of the last [`self_check`](Self::self_check), per servo slot.
+    pub fn self_check_results(&self) -> &[JogResult] {
+        &self.self_check_results
+    }
+
+    /// Jog servo slot `i` both ways and return it to its start position.
+    ///
+    /// Both targets stay inside `[0, ticks_per_rev - 1]`.  Near the top of
+    /// the range the backward jog goes first, so each jog is judged against
+    /// a travel of at least `jog.ticks`.  Whatever happens once torque is
+    /// on, the speed limit is then cleared and, in follower mode, torque is
+    /// disabled again.
+    fn jog_servo(&mut self, ctx: &CuContext, i: usize) -> CuResult<JogResult> {
+        let id = self.ids[i];
+        let Ok(start) = self.read_present_position(id) else {
+            return Ok(JogResult {
+                id,
+                start: self.cached_positions[i],
+                forward: JogVerdict::NoFeedback,
+                backward: JogVerdict::NoFeedback,
+            });
+        };
+        self.set_torque(id, true).map_err(|e| {
+            CuError::new_with_cause(
+                &format!(
+                    "Feetech: failed to enable torque for self-check on servo {}",
+                    id
+                ),
+                e,
+            )
+        })?;
+        let result = self.run_jog(ctx, i, start);
+
+        // Clear the speed limit and release the servo, whatever happened.
+        let speed = self.write_u16(id, reg::GOAL_SPEED, 0, "speed limit");
+        // Follower mode keeps torque off once the check is done.
+        if !self.has_writers
+            && let Err(e) = self.set_torque(id, false)
+        {
+            warning!(
+                "FeetechBridge: failed to disable torque on servo {} after the self-check: {}",
+                id,
+                e.to_string()
+            );
+        }
+        if result.is_err()
+            && let Err(e) = &speed
+        {
+            warning!(
+                "FeetechBridge: failed to clear the speed limit on servo {} after the self-check: {}",
+                id,
+                e.to_string()
+            );
+        }
+        result.and_then(|result| speed.map(|()| result))
+    }
+
+    /// Body of [`jog_servo`](Self::jog_servo) once torque is on: jog from
+    /// `start` both ways and back.
+    fn run_jog(&mut self, ctx: &CuContext, i: usize, start: u16) -> CuResult<JogResult> {
+        let id = self.ids[i];
+        let jog = self.jog;
+        self.write_u16(id, reg::GOAL_SPEED, jog.speed, "jog speed")?;
+
+        let top = self.ticks_per_rev.saturating_sub(1).min(u16::MAX as u32) as u16;
+        let start = start.min(top);
+        let up = start.saturating_add(jog.ticks).min(top);
+        let down = start.saturating_sub(jog.ticks);
+
+        let (forward, backward) = if up - start >= jog.ticks {
+            let reached = self.jog_to(ctx, id, up)?;
+            let forward = evaluate_jog(start, up, reached, jog.tolerance);
+            let from = reached.unwrap_or(up);
+            let reached = self.jog_to(ctx, id, down)?;
+            (forward, evaluate_jog(from, down, reached, jog.tolerance))
+        } else {
+            let reached = self.jog_to(ctx, id, down)?;
+            let backward = evaluate_jog(start, down, reached, jog.tolerance);
+            let from = reached.unwrap_or(down);
+            let reached = self.jog_to(ctx, id, up)?;
+            (evaluate_jog(from, up, reached, jog.tolerance), backward)
+        };
+
+        self.jog_to(ctx, id, start)?;
+        Ok(JogResult {
+            id,
+            start,
+            forward,
+            backward,
+        })
+    }
+
+    /// Command `target` and poll until the servo is within tolerance of it
+    /// or the settle time ran out.  Returns the last position read, if any.
+    fn jog_to(&mut self, ctx: &CuContext, id: u8, target: u16) -> CuResult<Option<u16>> {
+        self.write_u16(id, reg::GOAL_POSITION, target, "jog goal")?;
+        let deadline = ctx.now() + self.jog.settle;
+        let mut last = None;
+        while ctx.now() <= deadline {
+            if let Ok(present) = self.read_present_position(id) {
+                last = Some(present);
+                if present.abs_diff(target) <= self.jog.tolerance {
+                    break;
+                }
+            }
+            std::thread::sleep(std::time::Duration::from_millis(JOG_POLL_INTERVAL_MS));
+        }
+        Ok(last)
+    }
+
+    /// `true` while the e-stop is latched.
+    pub fn is_estopped(&self) -> bool {
+        self.estopped
+    }
+
+    /// Cut torque on every servo, carrying on past servos that do not answer.
+    fn engage_estop(&mut self) {
+        for i in 0..self.num_servos as usize {
+            if let Err(e) = self.set_torque(self.ids[i], false) {
+                error!(
+                    "FeetechBridge: e-stop failed to disable torque on servo {}: {}",
+                    self.ids[i],
+                    e.to_string()
+                );
+            }
+        }
+        for smoother in &mut self.smoothers {
+            smoother.reset();
+        }
+    }
+
+    /// Leave the e-stop: hold where the arm is, then restore torque if goals
+    /// are being sent.
+    fn release_estop(&mut self) -> CuResult<()> {
+        self.hold_present_positions()?;
+        if self.has_writers {
+            self.enable_all_torque()?;
+        }
+        Ok(())
+    }
+
+    /// Enable torque on every configured servo.
+    fn enable_all_torque(&mut self) -> CuResult<()> {
+        for i in 0..self.num_servos as usize {
+            self.set_torque(self.ids[i], true).map_err(|e| {
+                CuError::new_with_cause(
+                    &format!("Feetech: failed to enable torque on servo {}", self.ids[i]),
+                    e,
+                )
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Disable torque on every servo, logging the ones that do not answer.
+    fn disable_all_torque(&mut self) {
+        for i in 0..self.num_servos as usize {
+            if let Err(e) = self.set_torque(self.ids[i], false) {
+                debug!(
+                    "FeetechBridge: failed to disable torque on servo {}: {}",
+                    self.ids[i],
+                    e.to_string()
+                );
+            }
+        }
+    }
+
+    /// Run one step of the startup sequence.
+    fn run_startup_step(
+        &mut self,
+        step: &StartupStep,
+        hook: Option<StartupHook>,
+        ctx: &CuContext,
+    ) -> CuResult<()> {
+        match step {
+            StartupStep::Ping => {
+                for i in 0..self.num_servos as usize {
+                    let id = self.ids[i];
+                    self.ping(id).map_err(|e| {
+                        CuError::new_with_cause(&format!("Feetech: servo {id} did not answer"), e)
+                    })?;
+                }
+            }
+            StartupStep::CheckHome => self.check_home(),
+            StartupStep::Homing => self.run_homing(ctx)?,
+            StartupStep::SelfCheck => {
+                self.self_check(ctx)?;
+            }
+            StartupStep::Torque if self.has_writers => {
+                self.enable_all_torque()?;
+                debug!(
+                    "FeetechBridge: enabled torque on {} servos",
+                    self.num_servos
+                );
+            }
+            StartupStep::Torque => debug!(
+                "FeetechBridge: read-only mode, torque left disabled on {} servos",
+                self.num_servos
+            ),
+            StartupStep::Hook(_) => {
+                if let Some(hook) = hook {
+                    hook(self, ctx)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Progress of the startup sequence.
+    pub fn startup_state(&self) -> StartupState {
+        self.startup.state()
+    }
+
+    /// The startup steps, in the order `start` runs them.
+    pub fn startup_steps(&self) -> impl Iterator<Item = &StartupStep> {
+        self.startup.steps()
+    }
+
+    /// Start timing a bridge callback, if profiling.
+    fn profile_enter(&mut self, ctx: &CuContext) -> Option<CuTime> {
+        self.profiler.as_mut().map(|p| p.enter(&ctx.clock))
+    }
+
+    /// Finish timing a bridge callback started with `profile_enter`.
+    fn profile_leave(&mut self, entered: Option<CuTime>) {
+        if let (Some(profiler), Some(entered)) = (&mut self.profiler, entered) {
+            profiler.leave(entered);
+        }
+    }
+
+    /// Start timing a cycle phase, if profiling.
+    fn phase_start(&self) -> Option<PhaseStart> {
+        self.profiler.as_ref()?.start()
+    }
+
+    /// Add the time since `started` to `phase`, minus nested phases.
+    fn phase_end(&mut self, phase: Phase, started: Option<PhaseStart>) {
+        if let (Some(profiler), Some(started)) = (&mut self.profiler, started) {
+            profiler.add(phase, started);
+        }
+    }
+
+    /// Per-phase timing of the last finished cycle, when profiling.
+    pub fn cycle_profile(&self) -> Option<CycleProfile> {
+        self.profiler.as_ref()?.last()
+    }
+
+    /// Body of [`preprocess`](CuBridge::preprocess).
+    fn poll_bus(&mut self, ctx: &CuContext) -> CuResult<()> {
+        if !self.has_readers {
+            return Ok(());
+        }
+        let failed = self.read_all_positions()?;
+        self.last_read_time = ctx.now();
+        self.last_read_stamp = self.stamp.stamp(self.last_read_time);
+        if self.stamp.clock() == StampClock::Ros2
+            && !self.stamp.has_ros_time()
+            && !self.ros_clock_warned
+        {
+            warning!("FeetechBridge: no /clock received on ros_clock yet, positions are stamped 0");
+            self.ros_clock_warned = true;
+        }
+        self.cycle_seq = self.cycle_seq.wrapping_add(1);
+        self.record_cycle(failed);
+        self.poll_diagnostics();
+        for (i, &failed) in failed.iter().enumerate().take(self.num_servos as usize) {
+            if !failed {
+                self.observe_stuck(i, self.cached_positions[i]);
+            }
+        }
+        self.update_write_skips();
+        let full_read = !failed.contains(&true);
+        let was_ready = self.ready_gate.is_ready();
+        if self.ready_gate.observe(full_read) && !was_ready {
+            debug!(
+                "FeetechBridge: ready after {} consecutive full reads",
+                self.ready_gate.threshold
+            );
+        }
+        if let Some(auto) = &mut self.auto_calibration
+            && let Err(e) = auto.save_if_due(ctx.now())
+        {
+            warning!(
+                "FeetechBridge: failed to save calibration: {}",
+                e.to_string()
+            );
+        }
+        self.stream_to_foxglove();
+        Ok(())
+    }
+
+    /// Address the Foxglove stream listens on, when `"foxglove_port"` is set.
+    pub fn foxglove_addr(&self) -> Option<SocketAddr> {
+        self.foxglove.as_ref().map(FoxgloveServer::local_addr)
+    }
+
+    /// Queue this cycle's positions and diagnostics for subscribed Foxglove
+    /// clients.  Positions wait for the ready gate like the Rx channels.
+    fn stream_to_foxglove(&self) {
+        let Some(server) = &self.foxglove else {
+            return;
+        };
+        let n = self.num_servos as usize;
+        if server.wants_positions() && self.ready_gate.is_ready() {
+            server.send_positions(PositionsSample {
+                seq: self.cycle_seq,
+                stamp_ns: self.last_read_stamp,
+                units: self.output_units,
+                ids: self.ids[..n].to_vec(),
+                positions: (0..n).map(|i| self.present_value(i)).collect(),
+            });
+        }
+        let diagnostics_read = diagnostics_due(
+            self.cycle_seq,
+            self.diagnostics_interval,
+            self.diagnostics_phase,
+        );
+        if server.wants_diagnostics() && (self.diagnostics_interval == 0 || diagnostics_read) {
+            server.send_diagnostics(DiagnosticsSample {
+                seq: self.cycle_seq,
+                stamp_ns: self.last_read_stamp,
+                servos: self.health().iter().map(ServoStatus::from).collect(),
+            });
+        }
+    }
+
+    /// Body of [`receive`](CuBridge::receive).
+    fn publish_rx<'a, Payload>(
+        &mut self,
+        channel: &'static BridgeChannel<RxId, Payload>,
+        msg: &mut CuMsg<Payload>,
+    ) -> CuResult<()>
+    where
+        Payload: CuMsgPayload + 'a,
+    {
+        // Stamp the message with the time the positions were read.
+        msg.tov = Tov::Time(self.last_read_time);
+        // Not ready yet: publish nothing rather than a partial sample.
+        let ready = self.ready_gate.is_ready();
+
+        match channel.id() {
+            RxId::Positions => {
+                let pos_msg: &mut CuMsg<JointPositions> = msg.downcast_mut()?;
+                if ready {
+                    let started = self.phase_start();
+                    let mut payload = self.present_payload();
+                    payload.seq = self.cycle_seq;
+                    payload.stamp_ns = self.last_read_stamp;
+                    self.phase_end(Phase::Convert, started);
+                    let started = self.phase_start();
+                    pos_msg.set_payload(payload);
+                    self.phase_end(Phase::Publish, started);
+                } else {
+                    pos_msg.clear_payload();
+                }
+            }
+            RxId::Profile => {
+                let profile_msg: &mut CuMsg<CycleProfile> = msg.downcast_mut()?;
+                match self.cycle_profile() {
+                    Some(profile) => profile_msg.set_payload(profile),
+                    None => profile_msg.clear_payload(),
+                }
+            }
+            RxId::Velocities => {
+                let vel_msg: &mut CuMsg<JointVelocities> = msg.downcast_mut()?;
+                if ready {
+                    let started = self.phase_start();
+                    let payload = self.velocity_payload();
+                    self.phase_end(Phase::Convert, started);
+                    let started = self.phase_start();
+                    vel_msg.set_payload(payload);
+                    self.phase_end(Phase::Publish, started);
+                } else {
+                    vel_msg.clear_payload();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Body of [`send`](CuBridge::send).
+    fn record_tx<'a, Payload>(
+        &mut self,
+        ctx: &CuContext,
+        channel: &'static BridgeChannel<TxId, Payload>,
+        msg: &CuMsg<Payload>,
+    ) -> CuResult<()>
+    where
+        Payload: CuMsgPayload + 'a,
+    {
+        match channel.id() {
+            TxId::GoalPositions => {
+                let goal_msg: &CuMsg<JointPositions> = msg.downcast_ref()?;
+                let Some(positions) = goal_msg.payload() else {
+                    return Ok(());
+                };
+                if let Some(max_age) = self.max_command_age {
+                    // Without a tov the age is unknown: fail closed.
+                    let refused = match command_age(&goal_msg.tov, ctx.now()) {
+                        None => Some("without a tov".to_string()),
+                        Some(age) if age > max_age => Some(format!("{age} old (max {max_age})")),
+                        Some(_) => None,
+                    };
+                    if let Some(reason) = refused {
+                        self.warn_refused_goal(ctx.now(), &reason);
+                        if self.stale_action == StaleCommandAction::Stop {
+                            self.pending.goal = Some(GoalCommand::HoldPresent);
+                        }
+                        return Ok(());
+                    }
+                }
+                self.pending.goal = Some(GoalCommand::Positions(positions.clone()));
+            }
+            TxId::Estop => {
+                let estop_msg: &CuMsg<EStop> = msg.downcast_ref()?;
+                if let Some(estop) = estop_msg.payload() {
+                    self.pending.estop = Some(estop.engaged);
+                }
+            }
+            TxId::RosClock => {
+                let clock_msg: &CuMsg<RosTime> = msg.downcast_ref()?;
+                if let Some(time) = clock_msg.payload() {
+                    self.stamp.observe_ros_clock(time, ctx.now());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Log a refused goal command, at most once per
+    /// [`REFUSED_GOAL_LOG_INTERVAL_MS`] with the number refused meanwhile.
+    fn warn_refused_goal(&mut self, now: CuTime, reason: &str) {
+        self.refused_goals += 1;
+        let interval = CuDuration::from_millis(REFUSED_GOAL_LOG_INTERVAL_MS);
+        if self
+            .refused_logged_at
+            .is_some_and(|last| now - last < interval)
+        {
+            return;
+        }
+        warning!(
+            "FeetechBridge: refusing goal positions {} ({} refused since the last report)",
+            reason.to_string(),
+            self.refused_goals
+        );
+        self.refused_logged_at = Some(now);
+        self.refused_goals = 0;
+    }
+
+    /// Body of [`postprocess`](CuBridge::postprocess).
+    fn apply_pending(&mut self) -> CuResult<()> {
+        match self.pending.resolve(&mut self.estopped) {
+            ResolvedCommand::Idle => {}
+            ResolvedCommand::EStop => {
+                error!("FeetechBridge: e-stop engaged, torque disabled");
+                self.engage_estop();
+                self.dump_flight_recorder("e-stop engaged");
+            }
+            ResolvedCommand::Release => {
+                info!("FeetechBridge: e-stop released");
+                self.release_estop()?;
+            }
+            ResolvedCommand::Goal(GoalCommand::Positions(positions)) => {
+                self.sync_write_positions(&positions)?;
+            }
+            ResolvedCommand::Goal(GoalCommand::Group { slots, positions }) => {
+                let goals: HeaplessVec<(usize, f32), MAX_SERVOS> = slots
+                    .iter()
+                    .map(|&slot| slot as usize)
+                    .zip(positions.as_slice().iter().copied())
+                    .collect();
+                self.write_goals(&goals)?;
+            }
+            ResolvedCommand::Goal(GoalCommand::HoldPresent) => {
+                self.hold_present_positions()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// ===========================================================================
+// CuBridge trait implementation
+// ===========================================================================
+
+impl CuBridge for FeetechBridge {
+    type Tx = TxChannels;
+    type Rx = RxChannels;
+    type Resources<'r> = Resources;
+
+    /// Construct the bridge from configuration.
+    ///
+    /// Expected `copperconfig.ron` keys under the bridge's `config` block:
+    ///
+    /// | Key                | Type   | Description                                   |
+    /// |--------------------|--------|-----------------------------------------------|
+    /// | `servo0`           | u8     | Bus ID of the first servo                     |
+    /// | `servo1`           | u8     | Bus ID of the second servo                    |
+    /// | …                  | …      | Up to `servo7`                                |
+    /// | `units`            | string | `"raw"` (default), `"deg"`, `"rad"`, or `"normalize"` |
+    /// | `output_units`     | string | Unit of published positions (default: `units`) |
+    /// | `input_units`      | string | Unit of goal positions (default: `units`) |
+    /// | `velocity_units`   | string | Unit of published velocities, per second (default: `output_units`) |
+    /// | `calibration_file` | string | Path to calibration JSON (required if either unit is deg/rad/normalize, unless calibrated inline) |
+    /// | `calibration_min0`, `calibration_max0` | u16 | Inline calibration range of servo slot 0, used without `calibration_file` |
+    /// | …                  | …      | Up to `calibration_min7`, `calibration_max7` |
+    /// | `conversion_file`  | string | Exported conversion setup; replaces the unit, `ticks_per_rev`, `wrap_angles` and calibration keys |
+    /// | `ticks_per_rev`    | integer | Raw units per 360° (model-dependent; default 4096) |
+    /// | `wrap_angles`      | bool   | Wrap deg/rad into [-180, 180) / [-π, π) (default false) |
+    /// | `ready_after_cycles` | integer | Consecutive full reads before publishing (default 0) |
+    /// | `auto_calibrate`   | bool   | Widen calibration ranges from live reads and save them (default false) |
+    /// | `calibration_save_interval_ms` | u64 | Save changed calibration this often (default: only on stop) |
+    /// | `self_check_on_start` | bool | Run the jog self-check on start (default false) |
+    /// | `startup_sequence` | list   | Startup steps in order, e.g. `["ping", "check_home", "torque"]` (default: see [`startup`]) |
+    /// | `self_check_jog`   | u16    | Jog amplitude, raw ticks (default 100) |
+    /// | `self_check_tolerance` | u16 | Allowed jog error, raw ticks (default 30) |
+    /// | `self_check_speed` | u16    | Speed limit while jogging (default 200) |
+    /// | `self_check_settle_ms` | u64 | Time allowed per jog (default 1000) |
+    /// | `profile_log_cycles` | u32  | Log the mean per-phase cycle timing every N cycles (default 0: off) |
+    /// | `flight_recorder_depth` | u32 | Cycles of raw positions kept for fault dumps (disabled if absent) |
+    /// | `flight_recorder_file` | string | Dump file, appended to (default `feetech_flight_recorder.csv`) |
+    /// | `skip_write_on`    | list   | Error flags that exclude a servo from goal writes, e.g. `["overheat"]` |
+    /// | `diagnostics_interval` | u32 | Read load/voltage/temperature every N cycles (default 0: off) |
+    /// | `diagnostics_phase` | u32   | Cycle within the interval to read them on (default 0) |
+    /// | `stuck_cycles`     | u32    | Unchanged reads before a servo is flagged stuck (disabled if absent) |
+    /// | `stuck_threshold`  | u16    | Goal-to-present distance that counts as moving, raw ticks (default 20) |
+    /// | `homing0` .. `homing7` | string | `"min"` or `"max"`: home against that hard stop |
+    /// | `homing_speed<i>`  | u16    | Speed limit while homing (default 200) |
+    /// | `homing_load<i>`   | u16    | Stall load threshold, 0.1 % units (default 300) |
+    /// | `homing_torque<i>` | u16    | Torque limit while homing, 0.1 % units (default 500) |
+    /// | `homing_reference<i>` | u16 | Raw tick the stop should read as |
+    /// | `homing_timeout_ms` | integer | Per-servo homing timeout (default 10000) |
+    /// | `groups`           | map    | Group name → list of servo IDs (see [`groups`]) |
+    /// | `max_command_age_ms` | integer | Refuse goals whose `tov` is older than this, or missing |
+    /// | `stale_command_action` | string | `"hold"` (default) or `"stop"` |
+    /// | `goal_write`       | string | `"sync"` (default) or `"reg_write"` (REG_WRITE + ACTION) |
+    /// | `verify_sync_write` | bool  | Read goals back after a sync-write and re-write missed ones (default false) |
+    /// | `verify_reg_write` | string | With `reg_write`: `"off"` (default), `"log"`, or `"abort"` on an unlatched servo |
+    /// | `smoothing`        | f32    | Goal low-pass weight in (0, 1] for all joints (default 1.0 = off) |
+    /// | `max_step`         | f32    | Goal slew limit per cycle for all joints (default 0.0 = off) |
+    /// | `smoothing<i>` / `max_step<i>` | f32 | Per-joint overrides for slot `i` |
+    /// | `log_quantization` | bool   | Accumulate goal rounding error (default false) |
+    /// | `home0` .. `home7` | u16    | Expected raw position at startup, per servo |
+    /// | `home_tolerance`   | u16    | Allowed startup deviation in raw ticks (default 100) |
+    /// | `home_mismatch`    | string | `"warn"` (default) or `"offset"` |
+    /// | `stamp_clock`      | string | `"robot"` (default), `"wall"` or `"ros2"`: clock for `stamp_ns` |
+    /// | `foxglove_port`    | u16    | Serve a live Foxglove WebSocket stream on this port, 0 for any (off if absent) |
+    /// | `foxglove_host`    | string | Interface for the Foxglove stream (default `127.0.0.1`) |
+    ///
+    /// At least `servo0` must be present.
+    fn new(
+        config: Option<&ComponentConfig>,
+        tx_channels: &[BridgeChannelConfig<<Self::Tx as BridgeChannelSet>::Id>],
+        rx_channels: &[BridgeChannelConfig<<Self::Rx as BridgeChannelSet>::Id>],
+        resources: Self::Resources<'_>,
+    ) -> CuResult<Self>
+    where
+        Self: Sized,
+    {
+        let cfg = config.ok_or("FeetechBridge requires a config block with servo IDs")?;
+
+        // Collect servo IDs from sequential keys: "servo0", "servo1", …
+        // Stop at the first missing key (after servo0, which is mandatory).
+        let mut ids = [0u8; MAX_SERVOS];
+        let mut num_servos: u8 = 0;
+        for (i, id_slot) in ids.iter_mut().enumerate().take(MAX_SERVOS) {
+            let key = format!("servo{}", i);
+            match cfg.get::<u8>(&key)? {
+                Some(id) => {
+                    *id_slot = id;
+                    num_servos = (i + 1) as u8;
+                }
+                None if i == 0 => {
+                    return Err(
+                        "FeetechBridge: you must configure at least one servo ID (\"servo0\")"
+                            .into(),
+                    );
+                }
+                None => break, // no more servos configured
+            }
+        }
+
+        // ---- Portable conversion setup (replaces the unit and calibration keys) ----
+        // Loaded first so every check below runs on the setup actually used.
+        let conversion_path = cfg.get::<String>("conversion_file")?;
+        let conversion = match conversion_path.as_deref() {
+            Some(path) => {
+                let conversion =
+                    ConversionConfig::load(std::path::Path::new(path)).map_err(|e| {
+                        CuError::new_with_cause(
+                            &format!(
+                                "FeetechBridge: failed to load conversion setup from \"{path}\""
+                            ),
+                            e,
+                        )
+                    })?;
+                conversion
+                    .validate(&ids[..num_servos as usize])
+                    .map_err(|e| {
+                        CuError::from(format!(
+                            "FeetechBridge: invalid conversion config in \"{path}\": {e}"
+                        ))
+                    })?;
+                Some(conversion)
+            }
+            None => None,
+        };
+
+        // ---- Parse units (shared, then per direction) ----
+        let parse_units = |key: &str| -> CuResult<Option<Units>> {
+            match cfg.get::<String>(key)? {
+                Some(s) => s.parse().map(Some).map_err(|_| {
+                    CuError::from(format!(
+                        "FeetechBridge: unknown {key} \"{s}\". Use \"raw\", \"deg\", \"rad\", or \"normalize\"."
+                    ))
+                }),
+                None => Ok(None),
+            }
+        };
+        let (output_units, input_units, velocity_units) = match &conversion {
+            Some(conversion) => (
+                conversion.output_units,
+                conversion.input_units,
+                conversion.velocity_units,
+            ),
+            None => {
+                let units = parse_units("units")?.unwrap_or(Units::Raw);
+                let output_units = parse_units("output_units")?.unwrap_or(units);
+                let input_units = parse_units("input_units")?.unwrap_or(units);
+                let velocity_units = parse_units("velocity_units")?.unwrap_or(output_units);
+                (output_units, input_units, velocity_units)
+            }
+        };
+        let calibrated = needs_calibration(output_units, input_units, velocity_units);
+        let normalized = output_units == Units::Normalize
+            || input_units == Units::Normalize
+            || velocity_units == Units::Normalize;
+
+        let auto_calibrate = cfg.get::<bool>("auto_calibrate")?.unwrap_or(false);
+
+        // ---- Load calibration (required for deg / rad / normalize) ----
+        let mut centers = [0.0f32; MAX_SERVOS];
+        let mut half_ranges = [0.0f32; MAX_SERVOS];
+        let cal_path = cfg.get::<String>("calibration_file")?;
+        let load_calibration = |cal_path: &str| {
+            CalibrationData::load(std::path::Path::new(cal_path)).map_err(|e| {
+                CuError::new_with_cause(
+                    &format!("FeetechBridge: failed to load calibration from \"{cal_path}\""),
+                    e,
+                )
+            })
+        };
+        // Inline calibration: "calibration_min<i>" / "calibration_max<i>" per slot.
+        let mut inline = CalibrationData::default();
+        for (i, &id) in ids.iter().enumerate().take(num_servos as usize) {
+            let min = cfg.get::<u16>(&format!("calibration_min{i}"))?;
+            let max = cfg.get::<u16>(&format!("calibration_max{i}"))?;
+            match (min, max) {
+                (Some(min), Some(max)) if min <= max => {
+                    inline.servos.push(ServoCalibration { id, min, max })
+                }
+                (Some(min), Some(max)) => {
+                    return Err(format!(
+                        "FeetechBridge: calibration_min{i} ({min}) is greater than calibration_max{i} ({max})"
+                    )
+                    .into());
+                }
+                (None, None) => {}
+                _ => {
+                    return Err(format!(
+                        "FeetechBridge: calibration_min{i} and calibration_max{i} must be set together"
+                    )
+                    .into());
+                }
+            }
+        }
+
+        let mut loaded = None;
+        if calibrated {
+            // A conversion file takes precedence over a calibration file,
+            // which takes precedence over inline calibration.
+            let conversion = conversion.as_ref().zip(conversion_path.as_deref());
+            let (cal, source) = match (conversion, cal_path.as_deref()) {
+                (Some((conversion, path)), _) => (
+                    CalibrationData {
+                        servos: conversion.servos.clone(),
+                    },
+                    format!("\"{path}\""),
+                ),
+                (None, Some(cal_path)) => (load_calibration(cal_path)?, format!("\"{cal_path}\"")),
+                (None, None) if !inline.servos.is_empty() => {
+                    (inline, "inline calibration".to_string())
+                }
+                (None, None) => {
+                    return Err("FeetechBridge: \"calibration_file\" or inline calibration_min<i>/calibration_max<i> is required when units != raw".into());
+                }
+            };
+            for i in 0..num_servos as usize {
+                centers[i] = cal.center_for(ids[i]).ok_or_else(|| {
+                    CuError::from(format!(
+                        "FeetechBridge: no calibration entry for servo ID {} in {source}",
+                        ids[i]
+                    ))
+                })?;
+                if normalized {
+                    half_ranges[i] = cal.half_range_for(ids[i]).ok_or_else(|| {
+                        CuError::from(format!(
+                            "FeetechBridge: no calibration entry for servo ID {} in {source} (normalize)",
+                            ids[i]
+                        ))
+                    })?;
+                    if half_ranges[i] == 0.0 {
+                        return Err(format!(
+                            "FeetechBridge: servo ID {} has an empty range in {source} (normalize)",
+                            ids[i]
+                        )
+                        .into());
+                    }
+                }
+            }
+            loaded = Some(cal);
+        } else if cal_path.is_some() && !inline.servos.is_empty() {
+            debug!("FeetechBridge: calibration_file set, inline calibration ignored");
+        }
+        let calibration = CalibrationData {
+            servos: ids[..num_servos as usize]
+                .iter()
+                .filter_map(|id| loaded.as_ref()?.servos.iter().find(|s| s.id == *id))
+                .cloned()
+                .collect(),
+        };
+
+        // ---- Auto-calibration (widen ranges from live reads, save back) ----
+        let auto_calibration = if auto_calibrate {
+            let cal_path = cal_path
+                .ok_or("FeetechBridge: \"calibration_file\" is required for auto_calibrate")?;
+            // Start from scratch if the file does not exist yet.
+            let data = match loaded {
+                Some(cal) => cal,
+                None if std::path::Path::new(&cal_path).exists() => load_calibration(&cal_path)?,
+                None => CalibrationData::default(),
+            };
+            let interval = cfg
+                .get::<u64>("calibration_save_interval_ms")?
+                .map(CuDuration::from_millis);
+            Some(AutoCalibration::new(data, cal_path.into(), interval))
+        } else {
+            None
+        };
+
+        // ---- Ticks per revolution (model-dependent; used for deg/rad) ----
+        let (ticks_per_rev, wrap_angles) = match &conversion {
+            Some(conversion) => (conversion.ticks_per_rev, conversion.wrap_angles),
+            None => (
+                cfg.get::<u32>("ticks_per_rev")?.unwrap_or(4096),
+                cfg.get::<bool>("wrap_angles")?.unwrap_or(false),
+            ),
+        };
+        if wrap_angles && output_units.full_turn().is_none() && input_units.full_turn().is_none() {
+            return Err(
+                "FeetechBridge: wrap_angles needs \"deg\" or \"rad\" output or input units".into(),
+            );
+        }
+
+        // ---- Stale command guard ----
+        let max_command_age = cfg
+            .get::<u64>("max_command_age_ms")?
+            .map(CuDuration::from_millis);
+        let stale_action = match cfg.get::<String>("stale_command_action")? {
+            Some(s) => s.parse().map_err(|_| {
+                CuError::from(format!(
+                    "FeetechBridge: unknown stale_command_action \"{s}\". Use \"hold\" or \"stop\"."
+                ))
+            })?,
+            None => StaleCommandAction::Hold,
+        };
+
+        // ---- Position stamp clock ----
+        let stamp_clock = match cfg.get::<String>("stamp_clock")? {
+            Some(s) => s.parse().map_err(|_| {
+                CuError::from(format!(
+                    "FeetechBridge: unknown stamp_clock \"{s}\". Use \"robot\", \"wall\" or \"ros2\"."
+                ))
+            })?,
+            None => StampClock::Robot,
+        };
+        let has_ros_clock = tx_channels.iter().any(|c| c.channel.id == TxId::RosClock);
+        if stamp_clock == StampClock::Ros2 && !has_ros_clock {
+            return Err(
+                "FeetechBridge: stamp_clock \"ros2\" needs the ros_clock Tx channel connected"
+                    .into(),
+            );
+        }
+        if stamp_clock != StampClock::Ros2 && has_ros_clock {
+            warning!(
+                "FeetechBridge: ros_clock is connected but stamp_clock is not \"ros2\"; /clock is ignored"
+            );
+        }
+
+        // ---- Goal write mode ----
+        let goal_write = match cfg.get::<String>("goal_write")? {
+            Some(s) => s.parse().map_err(|_| {
+                CuError::from(format!(
+                    "FeetechBridge: unknown goal_write \"{s}\". Use \"sync\" or \"reg_write\"."
+                ))
+            })?,
+            None => GoalWrite::Sync,
+        };
+        let verify_reg_write = match cfg.get::<String>("verify_reg_write")? {
+            Some(s) => s.parse().map_err(|_| {
+                CuError::from(format!(
+                    "FeetechBridge: unknown verify_reg_write \"{s}\". Use \"off\", \"log\", or \"abort\"."
+                ))
+            })?,
+            None => VerifyRegWrite::Off,
+        };
+        if verify_reg_write != VerifyRegWrite::Off && goal_write != GoalWrite::RegWrite {
+            return Err("FeetechBridge: verify_reg_write needs goal_write = \"reg_write\"".into());
+        }
+        let verify_sync_write = cfg.get::<bool>("verify_sync_write")?.unwrap_or(false);
+        if verify_sync_write && goal_write != GoalWrite::Sync {
+            return Err("FeetechBridge: verify_sync_write needs goal_write = \"sync\"".into());
+        }
+
+        // ---- Per-joint goal smoothing ----
+        let default_alpha = cfg.get::<f32>("smoothing")?.unwrap_or(1.0);
+        let default_max_step = cfg.get::<f32>("max_step")?.unwrap_or(0.0);
+        let mut smoothers = [JointSmoother::bypass(); MAX_SERVOS];
+        for (i, smoother) in smoothers.iter_mut().enumerate().take(num_servos as usize) {
+            let alpha = cfg
+                .get::<f32>(&format!("smoothing{}", i))?
+                .unwrap_or(default_alpha);
+            if !(alpha > 0.0 && alpha <= 1.0) {
+                return Err(format!(
+                    "FeetechBridge: smoothing for servo slot {i} must be in (0, 1], got {alpha}"
+                )
+                .into());
+            }
+            let max_step = cfg
+                .get::<f32>(&format!("max_step{}", i))?
+                .unwrap_or(default_max_step);
+            *smoother = JointSmoother::new(alpha, max_step);
+        }
+
+        let quantization = cfg
+            .get::<bool>("log_quantization")?
+            .unwrap_or(false)
+            .then(|| [QuantizationStats::default(); MAX_SERVOS]);
+
+        // ---- Expected home positions ----
+        let mut expected_home = [None; MAX_SERVOS];
+        for (i, home) in expected_home
+            .iter_mut()
+            .enumerate()
+            .take(num_servos as usize)
+        {
+            *home = cfg.get::<u16>(&format!("home{}", i))?;
+        }
+        let home_tolerance = cfg
+            .get::<u16>("home_tolerance")?
+            .unwrap_or(DEFAULT_HOME_TOLERANCE);
+        let home_mismatch = match cfg.get::<String>("home_mismatch")? {
+            Some(s) => s.parse().map_err(|_| {
+                CuError::from(format!(
+                    "FeetechBridge: unknown home_mismatch \"{s}\". Use \"warn\" or \"offset\"."
+                ))
+            })?,
+            None => HomeMismatch::Warn,
+        };
+        if home_mismatch == HomeMismatch::Offset && !calibrated {
+            return Err(
+                "FeetechBridge: home_mismatch \"offset\" requires units other than raw".into(),
+            );
+        }
+
+        // ---- Hard-stop homing ----
+        let mut homing = [None; MAX_SERVOS];
+        for (i, slot) in homing.iter_mut().enumerate().take(num_servos as usize) {
+            let Some(dir) = cfg.get::<String>(&format!("homing{}", i))? else {
+                continue;
+            };
+            let direction = dir.parse().map_err(|_| {
+                CuError::from(format!(
+                    "FeetechBridge: unknown homing{i} direction \"{dir}\". Use \"min\" or \"max\"."
+                ))
+            })?;
+            let reference = cfg.get::<u16>(&format!("homing_reference{}", i))?;
+            if reference.is_some() && !calibrated {
+                return Err(format!(
+                    "FeetechBridge: homing_reference{i} requires units other than raw"
+                )
+                .into());
+            }
+            *slot = Some(HomingConfig {
+                direction,
+                speed: cfg
+                    .get::<u16>(&format!("homing_speed{}", i))?
+                    .unwrap_or(DEFAULT_HOMING_SPEED),
+                load_threshold: cfg
+                    .get::<u16>(&format!("homing_load{}", i))?
+                    .unwrap_or(DEFAULT_HOMING_LOAD),
+                torque_limit: cfg
+                    .get::<u16>(&format!("homing_torque{}", i))?
+                    .unwrap_or(DEFAULT_HOMING_TORQUE),
+                reference,
+            });
+            if let Some(homing) = slot
+                && homing.torque_limit <= homing.load_threshold
+            {
+                return Err(format!(
+                    "FeetechBridge: homing_torque{i} ({}) must be above homing_load{i} ({}), or the stall is never detected",
+                    homing.torque_limit, homing.load_threshold
+                )
+                .into());
+            }
+        }
+        let homing_timeout = CuDuration::from_millis(
+            cfg.get::<u64>("homing_timeout_ms")?
+                .unwrap_or(DEFAULT_HOMING_TIMEOUT_MS),
+        );
+
+        // ---- Named servo groups ----
+        let groups = match cfg.get_value::<BTreeMap<String, Vec<u8>>>("groups")? {
+            Some(map) => ServoGroups::from_ids(&map, &ids[..num_servos as usize])?,
+            None => ServoGroups::default(),
+        };
+
+        // ---- Stuck position detection ----
+        let stuck_detector = match cfg.get::<u32>("stuck_cycles")? {
+            Some(cycles) => Some(StuckDetector::new(
+                cycles,
+                cfg.get::<u16>("stuck_threshold")?
+                    .unwrap_or(DEFAULT_STUCK_THRESHOLD),
+            )),
+            None => None,
+        };
+
+        // ---- Jog self-check ----
+        let jog = JogConfig {
+            ticks: cfg
+                .get::<u16>("self_check_jog")?
+                .unwrap_or(DEFAULT_JOG_TICKS),
+            tolerance: cfg
+                .get::<u16>("self_check_tolerance")?
+                .unwrap_or(DEFAULT_JOG_TOLERANCE),
+            speed: cfg
+                .get::<u16>("self_check_speed")?
+                .unwrap_or(DEFAULT_JOG_SPEED),
+            settle: CuDuration::from_millis(
+                cfg.get::<u64>("self_check_settle_ms")?
+                    .unwrap_or(DEFAULT_JOG_SETTLE_MS),
+            ),
+        };
+        if jog.ticks <= jog.tolerance {
+            return Err(format!(
+                "FeetechBridge: self_check_jog ({}) must be larger than self_check_tolerance ({})",
+                jog.ticks, jog.tolerance
+            )
+            .into());
+        }
+        let self_check_on_start = cfg.get::<bool>("self_check_on_start")?.unwrap_or(false);
+
+        // If no Tx channels are wired up in this mission, nobody will send
+        // goal positions → the arm is in read-only (follower / teach) mode.
+        let has_writers = tx_channels
+            .iter()
+            .any(|c| c.channel.id == TxId::GoalPositions);
+
+        // ---- Startup sequence ----
+        let hooks = resources
+            .startup_hooks
+            .map(|hooks| hooks.0)
+            .unwrap_or_default();
+        let startup = match cfg.get_value::<Vec<String>>("startup_sequence")? {
+            Some(names) => {
+                if self_check_on_start {
+                    return Err("FeetechBridge: self_check_on_start cannot be combined with startup_sequence; list \"self_check\" in the sequence instead".into());
+                }
+                let steps = names
+                    .iter()
+                    .map(|name| {
+                        name.parse().map_err(|_| {
+                            CuError::from(format!(
+                                "FeetechBridge: unknown startup step \"{name}\". Use \"ping\", \"check_home\", \"homing\", \"self_check\", \"torque\", or \"hook:<name>\"."
+                            ))
+                        })
+                    })
+                    .collect::<CuResult<Vec<StartupStep>>>()?;
+                let required = [
+                    (
+                        StartupStep::Homing,
+                        homing.iter().any(Option::is_some),
+                        "homing<i>",
+                    ),
+                    (
+                        StartupStep::CheckHome,
+                        expected_home.iter().any(Option::is_some),
+                        "home<i>",
+                    ),
+                ];
+                for (step, configured, key) in required {
+                    if configured && !steps.contains(&step) {
+                        return Err(format!(
+                            "FeetechBridge: startup_sequence leaves out \"{step}\" but {key} is configured"
+                        )
+                        .into());
+                    }
+                }
+                if has_writers && !steps.contains(&StartupStep::Torque) {
+                    warning!(
+                        "FeetechBridge: startup_sequence leaves out \"torque\" with goal writers connected; goals have no effect until torque is enabled"
+                    );
+                }
+                StartupSequence::new(steps, &hooks).map_err(|e| {
+                    CuError::from(format!("FeetechBridge: invalid startup_sequence: {e}"))
+                })?
+            }
+            None => StartupSequence::default_order(self_check_on_start),
+        };
+
+        // ---- Skip writes to servos reporting errors ----
+        let mut skip_write_on = ServoError::default();
+        for name in cfg
+            .get_value::<Vec<String>>("skip_write_on")?
+            .unwrap_or_default()
+        {
+            let flag = name.parse::<ServoError>().map_err(|_| {
+                CuError::from(format!(
+                    "FeetechBridge: unknown skip_write_on flag \"{name}\". Use \"voltage\", \"angle\", \"overheat\", \"overcurrent\", or \"overload\"."
+                ))
+            })?;
+            skip_write_on = skip_write_on.union(flag);
+        }
+
+        // ---- Decimated diagnostics ----
+        let diagnostics_interval = cfg.get::<u32>("diagnostics_interval")?.unwrap_or(0);
+        let diagnostics_phase = cfg.get::<u32>("diagnostics_phase")?.unwrap_or(0);
+        if diagnostics_interval != 0 && diagnostics_phase >= diagnostics_interval {
+            return Err(format!(
+                "FeetechBridge: diagnostics_phase ({diagnostics_phase}) must be less than diagnostics_interval ({diagnostics_interval})"
+            )
+            .into());
+        }
+
+        // ---- Cycle profiling ----
+        let profile_log_cycles = cfg.get::<u32>("profile_log_cycles")?.unwrap_or(0);
+        let profiler = (profile_log_cycles > 0
+            || rx_channels.iter().any(|c| c.channel.id == RxId::Profile))
+        .then(|| Profiler::new(profile_log_cycles));
+
+        // ---- Flight recorder ----
+        let recorder = cfg
+            .get::<u32>("flight_recorder_depth")?
+            .map(|depth| FlightRecorder::new(depth as usize));
+        let recorder_file = cfg
+            .get::<String>("flight_recorder_file")?
+            .unwrap_or_else(|| DEFAULT_RECORDER_FILE.to_string())
+            .into();
+
+        // ---- Startup ready gate ----
+        let ready_after_cycles = cfg.get::<u32>("ready_after_cycles")?.unwrap_or(0);
+
+        let port = resources.serial.0;
+
+        // ---- Live Foxglove stream ----
+        let foxglove = match cfg.get::<u16>("foxglove_port")? {
+            Some(port) => {
+                let host = cfg
+                    .get::<String>("foxglove_host")?
+                    .unwrap_or_else(|| DEFAULT_FOXGLOVE_HOST.to_string());
+                let server = FoxgloveServer::bind((host.as_str(), port)).map_err(|e| {
+                    CuError::new_with_cause(
+                        &format!("FeetechBridge: failed to serve Foxglove on {host}:{port}"),
+                        e,
+                    )
+                })?;
+                info!(
+                    "FeetechBridge: Foxglove stream on ws://{}",
+                    server.local_addr().to_string()
+                );
+                Some(server)
+            }
+            None => None,
+        };
+
+        // The profile channel does not need the bus; the Foxglove stream does.
+        let has_readers =
+            rx_channels.iter().any(|c| c.channel.id != RxId::Profile) || foxglove.is_some();
+        let read_velocities = rx_channels.iter().any(|c| c.channel.id == RxId::Velocities);
+
+        Ok(FeetechBridge {
+            port,
+            ids,
+            num_servos,
+            has_writers,
+            has_readers,
+            cycle_seq: 0,
+            last_read_time: CuTime::default(),
+            last_read_stamp: 0,
+            stamp: StampSource::new(stamp_clock),
+            ros_clock_warned: false,
+            cached_positions: [0u16; MAX_SERVOS],
+            position_known: [false; MAX_SERVOS],
+            read_velocities,
+            cached_speeds: [0i16; MAX_SERVOS],
+            velocity_units,
+            output_units,
+            input_units,
+            centers,
+            ticks_per_rev,
+            half_ranges,
+            wrap_angles,
+            calibration,
+            max_command_age,
+            stale_action,
+            refused_logged_at: None,
+            refused_goals: 0,
+            goal_write,
+            verify_reg_write,
+            reg_write_unacked: [false; MAX_SERVOS],
+            verify_sync_write,
+            smoothers,
+            quantization,
+            expected_home,
+            home_tolerance,
+            home_mismatch,
+            homing,
+            homing_timeout,
+            homed_positions: [None; MAX_SERVOS],
+            groups,
+            ready_gate: ReadyGate::new(ready_after_cycles),
+            stuck_detector,
+            stuck: [StuckState::default(); MAX_SERVOS],
+            auto_calibration,
+            jog,
+            startup,
+            self_check_results: Vec::new(),
+            pending: PendingCommands::default(),
+            estopped: false,
+            servo_errors: [ServoError::default(); MAX_SERVOS],
+            skip_write_on,
+            skipping: [false; MAX_SERVOS],
+            diagnostics_interval,
+            diagnostics_phase,
+            diagnostics: [None; MAX_SERVOS],
+            profiler,
+            recorder,
+            recorder_file,
+            foxglove,
+        })
+    }
+
+    /// Called once before the first processing cycle.
+    ///
+    /// Runs the startup sequence (see [`startup`]): by default checks servos
+    /// against their expected home positions, homes servos against their hard
+    /// stops, runs the jog self-check if configured, then enables torque.
+    /// Torque is enabled only when writers are connected (commander mode).
+    /// In follower mode torque stays off so the arm moves freely.
+    ///
+    /// If a step fails the remaining ones are skipped, torque is disabled on
+    /// every servo and the error names the failed step.
+    ///
+    /// With `"wrap_angles"`, every present position is read first so wrapped
+    /// goals can be unwrapped before the bus is polled.
+    fn start(&mut self, ctx: &CuContext) -> CuResult<()> {
+        if self.wrap_angles {
+            self.read_unwrap_references();
+        }
+        self.startup.reset();
+        while let Some((step, hook)) = self.startup.advance() {
+            debug!("FeetechBridge: startup step {}", step.to_string());
+            if let Err(e) = self.run_startup_step(&step, hook, ctx) {
+                self.startup.fail();
+                self.disable_all_torque();
+                return Err(CuError::new_with_cause(
+                    &format!("FeetechBridge: startup step \"{step}\" failed"),
+                    e,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle an outgoing message on a Tx channel.
+    ///
+    /// Commands are only recorded here and applied in
+    /// [`postprocess`](CuBridge::postprocess), by priority.  A
+    /// `goal_positions` command older than `max_command_age_ms` is refused.
+    fn send<'a, Payload>(
+        &mut self,
+        ctx: &CuContext,
+        channel: &'static BridgeChannel<<Self::Tx as BridgeChannelSet>::Id, Payload>,
+        msg: &CuMsg<Payload>,
+    ) -> CuResult<()>
+    where
+        Payload: CuMsgPayload + 'a,
+    {
+        let entered = self.profile_enter(ctx);
+        let result = self.record_tx(ctx, channel, msg);
+        self.profile_leave(entered);
+        result
+    }
+
+    /// Apply the highest-priority command received this cycle.
+    fn postprocess(&mut self, ctx: &CuContext) -> CuResult<()> {
+        let entered = self.profile_enter(ctx);
+        let result = self.apply_pending();
+        self.profile_leave(entered);
+        result
+    }
+
+    /// Poll the bus once per cycle when any Rx channel but `profile` is connected.
+    ///
+    /// Reads every servo's present position, advances the cycle sequence
+    /// number and updates the ready gate.
+    fn preprocess(&mut self, ctx: &CuContext) -> CuResult<()> {
+        if let Some(profiler) = &mut self.profiler
+            && let Some(mean) = profiler.finish_cycle()
+        {
+            info!(
+                "FeetechBridge: mean cycle {} (write {}, wait {}, convert {}, publish {}, other {})",
+                mean.total, mean.write, mean.wait, mean.convert, mean.publish, mean.other
+            );
+        }
+        let entered = self.profile_enter(ctx);
+        let result = self.poll_bus(ctx);
+        self.profile_leave(entered);
+        result
+    }
+
+    /// Produce an incoming message on an Rx channel.
+    ///
+    /// Publishes the positions polled in [`preprocess`](CuBridge::preprocess):
+    /// as a [`JointPositions`] tagged with the cycle sequence number on
+    /// `positions`, and the matching speeds on `velocities`.
+    fn receive<'a, Payload>(
+        &mut self,
+        ctx: &CuContext,
+        channel: &'static BridgeChannel<<Self::Rx as BridgeChannelSet>::Id, Payload>,
+        msg: &mut CuMsg<Payload>,
+    ) -> CuResult<()>
+    where
+        Payload: CuMsgPayload + 'a,
+    {
+        let entered = self.profile_enter(ctx);
+        let result = self.publish_rx(channel, msg);
+        self.profile_leave(entered);
+        result
+    }
+
+    /// Called once after the last processing cycle.
+    ///
+    /// Disables torque on every servo for safety (prevents the arm from
+    /// holding position with power applied after the application exits),
+    /// and saves auto-calibration data that changed since the last save.
+    fn stop(&mut self, ctx: &CuContext) -> CuResult<()> {
+        self.disable_all_torque();
+        debug!(
+            "FeetechBridge: disabled torque on {} servos",
+            self.num_servos
+        );
+        for smoother in &mut self.smoothers {
+            smoother.reset();
+        }
+        if let Some(auto) = &mut self.auto_calibration
+            && let Err(e) = auto.save_if_changed(ctx.now())
+        {
+            warning!(
+                "FeetechBridge: failed to save calibration: {}",
+                e.to_string()
+            );
+        }
+        if let Some(stats) = self.quantization_stats() {
+            for (i, s) in stats.iter().enumerate() {
+                info!(
+                    "FeetechBridge: servo {} quantization error over {} goals: max {} mean {}",
+                    self.ids[i],
+                    s.count,
+                    s.max_abs,
+                    s.mean_abs()
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+// ===========================================================================
+// Tests
+// ===========================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bincode::config::standard;
+    use bincode::de::DecoderImpl;
+    use bincode::de::read::SliceReader;
+    use bincode::encode_to_vec;
+    use serialport::{SerialPort, TTYPort};
+    use std::time::Duration;
+
+    /// Build a bridge through [`CuBridge::new`] on one end of a pseudo-terminal.
+    ///
+    /// The other end is returned so a test can play the servos' side of the bus.
+    fn test_bridge(cfg: ComponentConfig, tx: bool, rx: bool) -> (FeetechBridge, TTYPort) {
+        test_bridge_with_hooks(cfg, tx, rx, None)
+    }
+
+    /// [`test_bridge`], with `hooks` bound as the `startup_hooks` resource.
+    fn test_bridge_with_hooks(
+        cfg: ComponentConfig,
+        tx: bool,
+        rx: bool,
+        hooks: Option<StartupHooks>,
+    ) -> (FeetechBridge, TTYPort) {
+        let (bus, mut port) = TTYPort::pair().expect("pty pair");
+        port.set_timeout(Duration::from_millis(5))
+            .expect("pty timeout");
+        let resources = Resources {
+            serial: Owned(LinuxSerialPort::new(Box::new(port))),
+            startup_hooks: hooks.map(Owned),
+        };
+        let tx_channels: Vec<_> = tx
+            .then(|| BridgeChannelConfig::from_static(&TxChannels::GOAL_POSITIONS, None, None))
+            .into_iter()
+            .collect();
+        let rx_channels: Vec<_> = rx
+            .then(|| BridgeChannelConfig::from_static(&RxChannels::POSITIONS, None, None))
+            .into_iter()
+            .collect();
+        let bridge = FeetechBridge::new(Some(&cfg), &tx_channels, &rx_channels, resources)
+            .expect("bridge should build");
+        (bridge, bus)
+    }
+
+    /// Scratch directory for file fixtures, unique per test and process and
+    /// removed on drop.
+    struct TempDir(std::path::PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("feetech_{name}_{}", std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn path(&self, file: &str) -> std::path::PathBuf {
+            self.0.join(file)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            std::fs::remove_dir_all(&self.0).ok();
+        }
+    }
+
+    fn servo_config(ids: &[u8]) -> ComponentConfig {
+        let mut cfg = ComponentConfig::new();
+        for (i, &id) in ids.iter().enumerate() {
+            cfg.set(&format!("servo{i}"), id);
+        }
+        cfg
+    }
+
+    #[test]
+    fn checksum_matches_known_values() {
+        // Hand-calculated example:
+        //   ID=1, Length=4, Instruction=WRITE(3), Addr=40, Data=1
+        //   body = [0x01, 0x04, 0x03, 0x28, 0x01]
+        //   sum  = 1+4+3+40+1 = 49 = 0x31
         //   ~0x31 = 0xCE
         let body = [0x01u8, 0x04, 0x03, 0x28, 0x01];
         assert_eq!(compute_checksum(&body), 0xCE);
     }
 
     #[test]
-    fn joint_positions_from_slice() {
-        let mut p = JointPositions::new();
-        p.fill_from_iter([0.0f32, 32768.0, 65535.0]);
-        assert_eq!(p.as_slice(), &[0.0, 32768.0, 65535.0]);
+    fn joint_positions_from_slice() {
+        let mut p = JointPositions::new();
+        p.fill_from_iter([0.0f32, 32768.0, 65535.0]);
+        assert_eq!(p.as_slice(), &[0.0, 32768.0, 65535.0]);
+    }
+
+    #[test]
+    fn units_raw_roundtrip() {
+        use crate::calibration::Units;
+        let u = Units::Raw;
+        let tpr = 4096.0;
+        assert_eq!(u.from_raw(2048, 0.0, tpr), 2048.0);
+        assert_eq!(u.to_raw(2048.0, 0.0, tpr), 2048);
+    }
+
+    #[test]
+    fn units_deg_roundtrip() {
+        use crate::calibration::{DEFAULT_TICKS_PER_REV, Units};
+        let u = Units::Deg;
+        let center = 2048.0;
+        // Center should map to 0°.
+        assert!((u.from_raw(2048, center, DEFAULT_TICKS_PER_REV as f32)).abs() < 1e-6);
+        // Converting 0° back should give the center tick.
+        assert_eq!(u.to_raw(0.0, center, DEFAULT_TICKS_PER_REV as f32), 2048);
+    }
+
+    #[test]
+    fn units_rad_roundtrip() {
+        use crate::calibration::{DEFAULT_TICKS_PER_REV, Units};
+        let u = Units::Rad;
+        let center = 2048.0;
+        let rad = u.from_raw(3072, center, DEFAULT_TICKS_PER_REV as f32);
+        let back = u.to_raw(rad, center, DEFAULT_TICKS_PER_REV as f32);
+        assert_eq!(back, 3072);
+    }
+
+    #[test]
+    fn units_normalize_roundtrip() {
+        use crate::calibration::Units;
+        let u = Units::Normalize;
+        let center = 2048.0;
+        let half_range = 1024.0; // min=1024, max=3072
+        assert!((u.from_raw(2048, center, half_range)).abs() < 1e-6);
+        assert!((u.from_raw(1024, center, half_range) + 1.0).abs() < 1e-6);
+        assert!((u.from_raw(3072, center, half_range) - 1.0).abs() < 1e-6);
+        assert_eq!(u.to_raw(0.0, center, half_range), 2048);
+        assert_eq!(u.to_raw(-1.0, center, half_range), 1024);
+        assert_eq!(u.to_raw(1.0, center, half_range), 3072);
+    }
+
+    #[test]
+    fn deg_output_with_raw_input() {
+        let dir = TempDir::new("units");
+        let cal_path = dir.path("calibration.json");
+        CalibrationData {
+            servos: vec![crate::calibration::ServoCalibration {
+                id: 1,
+                min: 1024,
+                max: 3072,
+            }],
+        }
+        .save(&cal_path)
+        .unwrap();
+
+        let mut cfg = servo_config(&[1]);
+        cfg.set("output_units", "deg".to_string());
+        cfg.set("input_units", "raw".to_string());
+        cfg.set("calibration_file", cal_path.to_string_lossy().into_owned());
+        let (mut bridge, _bus) = test_bridge(cfg, true, true);
+        assert_eq!(bridge.output_units, Units::Deg);
+        assert_eq!(bridge.input_units, Units::Raw);
+
+        // Published in degrees: 1024 ticks past center is 90°.
+        bridge.cached_positions[0] = 3072;
+        assert!((bridge.present_value(0) - 90.0).abs() < 1e-4);
+        // Commanded in raw ticks: the goal passes through unconverted.
+        assert_eq!(bridge.goal_to_raw(0, 2500.0).unwrap(), 2500);
+    }
+
+    #[test]
+    fn conversion_config_round_trips() {
+        use crate::calibration::{ConversionConfig, ServoCalibration};
+
+        let dir = TempDir::new("conversion");
+        let cal_path = dir.path("calibration.json");
+        CalibrationData {
+            servos: vec![
+                ServoCalibration {
+                    id: 1,
+                    min: 1024,
+                    max: 3072,
+                },
+                // Not on this bus: left out of the export.
+                ServoCalibration {
+                    id: 9,
+                    min: 0,
+                    max: 4095,
+                },
+                ServoCalibration {
+                    id: 2,
+                    min: 500,
+                    max: 1500,
+                },
+            ],
+        }
+        .save(&cal_path)
+        .unwrap();
+
+        let mut cfg = servo_config(&[1, 2]);
+        cfg.set("output_units", "normalize".to_string());
+        cfg.set("input_units", "deg".to_string());
+        cfg.set("ticks_per_rev", 1024u32);
+        cfg.0.insert(
+            "wrap_angles".to_string(),
+            serde_json::from_str("true").unwrap(),
+        );
+        cfg.set("calibration_file", cal_path.to_string_lossy().into_owned());
+        let (source, _bus) = test_bridge(cfg, true, true);
+        let exported = source.export_config();
+        assert_eq!(
+            exported.servos.iter().map(|s| s.id).collect::<Vec<_>>(),
+            [1, 2]
+        );
+        let export_path = dir.path("arm.json");
+        exported.save(&export_path).unwrap();
+        assert_eq!(ConversionConfig::load(&export_path).unwrap(), exported);
+
+        // A raw bridge loading the file converts exactly like the source.
+        let mut cfg = servo_config(&[1, 2]);
+        cfg.set(
+            "conversion_file",
+            export_path.to_string_lossy().into_owned(),
+        );
+        let (mut copy, _bus2) = test_bridge(cfg, true, true);
+        assert_eq!(copy.export_config(), exported);
+        let mut source = source;
+        for raw in [500u16, 1100, 2000, 3072] {
+            source.cached_positions[1] = raw;
+            copy.cached_positions[1] = raw;
+            assert_eq!(source.present_value(1), copy.present_value(1));
+        }
+        source.cache_position(0, 2048);
+        copy.cache_position(0, 2048);
+        assert_eq!(
+            source.goal_to_raw(0, 45.0).unwrap(),
+            copy.goal_to_raw(0, 45.0).unwrap()
+        );
+
+        // Invalid setups are refused and leave the bridge untouched.
+        let mut missing = exported.clone();
+        missing.servos.retain(|s| s.id != 2);
+        assert!(copy.import_config(missing).is_err());
+        let mut inverted = exported.clone();
+        inverted.servos[0].min = 4000;
+        assert!(copy.import_config(inverted).is_err());
+        let mut future = exported.clone();
+        future.version += 1;
+        assert!(copy.import_config(future).is_err());
+        assert_eq!(copy.export_config(), exported);
+
+        // The file is what validation runs on: unit keys without a
+        // calibration_file are fine, and so are settings that need
+        // calibrated units.
+        let conversion_cfg = || {
+            let mut cfg = servo_config(&[1, 2]);
+            cfg.set(
+                "conversion_file",
+                export_path.to_string_lossy().into_owned(),
+            );
+            cfg
+        };
+        let mut cfg = conversion_cfg();
+        cfg.set("units", "deg".to_string());
+        cfg.set("home_mismatch", "offset".to_string());
+        cfg.set("homing0", "min".to_string());
+        cfg.set("homing_reference0", 1024u16);
+        let (mut offset, _bus3) = test_bridge(cfg, true, true);
+
+        // A raw setup would leave those settings without a calibration.
+        let raw_path = dir.path("raw.json");
+        let raw = ConversionConfig {
+            output_units: Units::Raw,
+            input_units: Units::Raw,
+            velocity_units: Units::Raw,
+            wrap_angles: false,
+            ..exported.clone()
+        };
+        raw.save(&raw_path).unwrap();
+        assert!(offset.import_config(raw.clone()).is_err());
+        assert_eq!(offset.export_config(), exported);
+        let mut cfg = conversion_cfg();
+        cfg.set("conversion_file", raw_path.to_string_lossy().into_owned());
+        cfg.set("home_mismatch", "offset".to_string());
+        let (_bus4, mut port) = TTYPort::pair().expect("pty pair");
+        port.set_timeout(Duration::from_millis(5))
+            .expect("pty timeout");
+        let resources = Resources {
+            serial: Owned(LinuxSerialPort::new(Box::new(port))),
+            startup_hooks: None,
+        };
+        assert!(FeetechBridge::new(Some(&cfg), &[], &[], resources).is_err());
+    }
+
+    #[test]
+    fn inline_calibration_builds_deg_bridge() {
+        let mut cfg = servo_config(&[1, 2]);
+        cfg.set("units", "deg".to_string());
+        cfg.set("calibration_min0", 1024u16);
+        cfg.set("calibration_max0", 3072u16);
+        cfg.set("calibration_min1", 0u16);
+        cfg.set("calibration_max1", 2048u16);
+        let (mut bridge, _bus) = test_bridge(cfg.clone(), true, true);
+        assert_eq!(bridge.centers[..2], [2048.0, 1024.0]);
+        // 1024 ticks past servo 2's center is 90°.
+        bridge.cached_positions[1] = 2048;
+        assert!((bridge.present_value(1) - 90.0).abs() < 1e-4);
+
+        // A calibration file takes precedence over the inline ranges.
+        let dir = TempDir::new("inline_cal");
+        let path = dir.path("calibration.json");
+        CalibrationData {
+            servos: vec![
+                crate::calibration::ServoCalibration {
+                    id: 1,
+                    min: 0,
+                    max: 1000,
+                },
+                crate::calibration::ServoCalibration {
+                    id: 2,
+                    min: 0,
+                    max: 1000,
+                },
+            ],
+        }
+        .save(&path)
+        .unwrap();
+        let mut with_file = cfg.clone();
+        with_file.set("calibration_file", path.to_string_lossy().into_owned());
+        let (bridge, _bus) = test_bridge(with_file, true, true);
+        assert_eq!(bridge.centers[..2], [500.0, 500.0]);
+
+        // Validated like a file: every servo needs an entry, and ranges must be ordered.
+        let build = |cfg: ComponentConfig| {
+            let (_bus, port) = TTYPort::pair().expect("pty pair");
+            let resources = Resources {
+                serial: Owned(LinuxSerialPort::new(Box::new(port))),
+                startup_hooks: None,
+            };
+            FeetechBridge::new(Some(&cfg), &[], &[], resources)
+        };
+        let mut missing = servo_config(&[1, 2]);
+        missing.set("units", "normalize".to_string());
+        missing.set("calibration_min0", 1024u16);
+        missing.set("calibration_max0", 3072u16);
+        assert!(build(missing).is_err());
+        let mut inverted = servo_config(&[1]);
+        inverted.set("units", "deg".to_string());
+        inverted.set("calibration_min0", 3072u16);
+        inverted.set("calibration_max0", 1024u16);
+        assert!(build(inverted).is_err());
+        // An empty range is fine for degrees but cannot be normalized.
+        let mut empty = servo_config(&[1]);
+        empty.set("units", "deg".to_string());
+        empty.set("calibration_min0", 2048u16);
+        empty.set("calibration_max0", 2048u16);
+        assert!(build(empty.clone()).is_ok());
+        empty.set("units", "normalize".to_string());
+        assert!(build(empty).is_err());
+    }
+
+    #[test]
+    fn units_deg_wrap_boundary() {
+        use crate::calibration::{DEFAULT_TICKS_PER_REV, Units};
+        let u = Units::Deg;
+        let center = 2048.0;
+        let tpr = DEFAULT_TICKS_PER_REV as f32;
+        // 2100 ticks past center is ~184.6°, which wraps to ~-175.4°.
+        let unwrapped = u.from_raw(4148, center, tpr);
+        let wrapped = u.wrap(unwrapped);
+        assert!((wrapped - (unwrapped - 360.0)).abs() < 1e-3);
+        assert!((-180.0..180.0).contains(&wrapped));
+        // Exactly +180° wraps to -180°.
+        assert!((u.wrap(180.0) + 180.0).abs() < 1e-6);
+        // Near the present position (4140 ticks) the wrapped goal unwraps
+        // back across the boundary to the same tick.
+        let reference = u.from_raw(4140, center, tpr);
+        let goal = u.unwrap_near(wrapped, reference);
+        assert_eq!(u.to_raw(goal, center, tpr), 4148);
+        // Near the center the same wrapped goal stays on the negative side.
+        let goal = u.unwrap_near(wrapped, 0.0);
+        assert_eq!(u.to_raw(goal, center, tpr), 4148 - 4096);
+    }
+
+    #[test]
+    fn wrapped_goal_unwraps_against_position_read_on_start() {
+        let mut cfg = servo_config(&[1]);
+        cfg.set("units", "deg".to_string());
+        cfg.set("calibration_min0", 0u16);
+        cfg.set("calibration_max0", 4096u16);
+        cfg.0.insert(
+            "wrap_angles".to_string(),
+            serde_json::from_str("true").unwrap(),
+        );
+        // Write-only: nothing polls the bus.
+        let (mut bridge, mut bus) = test_bridge(cfg.clone(), true, false);
+        let (ctx, _clock) = CuContext::new_mock_clock();
+        // Without a reading there is nothing to unwrap against.
+        assert!(bridge.goal_to_raw(0, -175.0).is_err());
+
+        // The servo sits at 4140 ticks (~183.9°); then acknowledges torque on.
+        bus.write_all(&status_packet(1, 0, &4140u16.to_le_bytes()))
+            .unwrap();
+        bus.write_all(&status_packet(1, 0, &[])).unwrap();
+        bridge.start(&ctx).unwrap();
+        // -175° resolves to +185° next to it, not to tick 57 across the range.
+        assert_eq!(bridge.goal_to_raw(0, -175.0).unwrap(), 4153);
+
+        // Wrapping without an angle unit is refused.
+        cfg.set("units", "normalize".to_string());
+        let (_bus, port) = TTYPort::pair().expect("pty pair");
+        let resources = Resources {
+            serial: Owned(LinuxSerialPort::new(Box::new(port))),
+            startup_hooks: None,
+        };
+        assert!(FeetechBridge::new(Some(&cfg), &[], &[], resources).is_err());
+    }
+
+    #[test]
+    fn group_sync_write_only_targets_group_members() {
+        let mut cfg = servo_config(&[1, 2, 3, 4, 5, 6]);
+        cfg.0.insert(
+            "groups".to_string(),
+            serde_json::from_str(r#"{"arm": [1, 2, 3, 4, 5], "gripper": [6]}"#).unwrap(),
+        );
+        let (mut bridge, mut bus) = test_bridge(cfg, true, false);
+        let (ctx, _clock) = CuContext::new_mock_clock();
+        assert_eq!(
+            bridge.groups().get("gripper").unwrap().slots.as_slice(),
+            &[5]
+        );
+
+        // Command the gripper: the packet must address servo 6 and nothing else.
+        // Group goals are queued and only written in postprocess.
+        bridge.write_group_positions("gripper", &[1234.0]).unwrap();
+        assert!(drain(&mut bus).is_empty());
+        bridge.postprocess(&ctx).unwrap();
+        let gripper_goal = [reg::GOAL_POSITION, 2, 6, 0xD2, 0x04];
+        assert_eq!(
+            drain(&mut bus),
+            packet(BROADCAST_ID, instr::SYNC_WRITE, &gripper_goal)
+        );
+
+        // Group order is kept, and a count mismatch writes nothing.
+        bridge
+            .write_group_positions("arm", &[100.0, 200.0, 300.0, 400.0, 500.0])
+            .unwrap();
+        bridge.postprocess(&ctx).unwrap();
+        let mut params = [0u8; MAX_PACKET_SIZE - 5];
+        let entries = [(1, 100), (2, 200), (3, 300), (4, 400), (5, 500)];
+        let n = build_goal_sync_write(&entries, &mut params).unwrap();
+        assert_eq!(
+            drain(&mut bus),
+            packet(BROADCAST_ID, instr::SYNC_WRITE, &params[..n])
+        );
+        assert!(bridge.write_group_positions("arm", &[0.0]).is_err());
+        bridge.postprocess(&ctx).unwrap();
+        assert!(drain(&mut bus).is_empty());
+
+        // A servo in the skip set is left out of group writes too.
+        bridge.skipping[0] = true;
+        bridge
+            .write_group_positions("arm", &[1.0, 2.0, 3.0, 4.0, 5.0])
+            .unwrap();
+        bridge.postprocess(&ctx).unwrap();
+        let entries = [(2, 2), (3, 3), (4, 4), (5, 5)];
+        let n = build_goal_sync_write(&entries, &mut params).unwrap();
+        assert_eq!(
+            drain(&mut bus),
+            packet(BROADCAST_ID, instr::SYNC_WRITE, &params[..n])
+        );
+
+        // An e-stop in the same cycle drops the queued group goal.
+        bridge.skipping[0] = false;
+        bridge.write_group_positions("gripper", &[10.0]).unwrap();
+        bridge.pending.estop = Some(true);
+        bridge.postprocess(&ctx).unwrap();
+        let torque_off = drain(&mut bus);
+        assert!(
+            !torque_off
+                .windows(3)
+                .any(|w| w == [reg::GOAL_POSITION, 2, 6])
+        );
+        assert!(bridge.write_group_positions("gripper", &[10.0]).is_err());
+    }
+
+    #[test]
+    fn group_rejects_unknown_servo_id() {
+        let mut cfg = BTreeMap::new();
+        cfg.insert("arm".to_string(), vec![1u8, 9]);
+        assert!(ServoGroups::from_ids(&cfg, &[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn units_deg_quantization_error() {
+        use crate::calibration::{DEFAULT_TICKS_PER_REV, QuantizationStats, Units};
+        let u = Units::Deg;
+        let center = 2048.0;
+        let tpr = DEFAULT_TICKS_PER_REV as f32;
+        // 10° is 113.78 ticks from center; it rounds to 114 ticks = 10.0195°.
+        let err = u.quantization_error(10.0, center, tpr);
+        assert!((err - (10.0 - 114.0 * 360.0 / 4096.0)).abs() < 1e-4);
+        // Exact ticks have no error, and no error ever exceeds half a tick.
+        assert!(u.quantization_error(90.0, center, tpr).abs() < 1e-4);
+        let half_tick = 180.0 / tpr;
+        let mut stats = QuantizationStats::default();
+        for k in 0..1000 {
+            let e = u.quantization_error(k as f32 * 0.037, center, tpr);
+            assert!(e.abs() <= half_tick + 1e-4);
+            stats.record(e);
+        }
+        assert_eq!(stats.count, 1000);
+        assert!(stats.max_abs <= half_tick + 1e-4);
+        assert!(stats.mean_abs() > 0.0 && stats.mean_abs() < stats.max_abs);
+
+        // The bridge records the same error for the goals it converts.
+        let mut cfg = servo_config(&[1]);
+        cfg.set("units", "deg".to_string());
+        cfg.set("calibration_min0", 0u16);
+        cfg.set("calibration_max0", 4096u16);
+        cfg.0.insert(
+            "log_quantization".to_string(),
+            serde_json::from_str("true").unwrap(),
+        );
+        let (mut bridge, _bus) = test_bridge(cfg, true, false);
+        assert_eq!(bridge.goal_to_raw(0, 10.0).unwrap(), 2048 + 114);
+        let stats = bridge.quantization_stats().unwrap()[0];
+        assert_eq!(stats.count, 1);
+        assert_eq!(stats.max_abs, u.quantization_error(10.0, center, tpr).abs());
+    }
+
+    #[test]
+    fn stale_command_is_detected_from_tov() {
+        let now = CuTime::from_millis(1_000);
+        let max_age = CuDuration::from_millis(50);
+        // Fresh command.
+        let fresh = Tov::Time(CuTime::from_millis(990));
+        assert!(command_age(&fresh, now).unwrap() <= max_age);
+        // Command from before a 200 ms stall.
+        let old = Tov::Time(CuTime::from_millis(800));
+        let age = command_age(&old, now).unwrap();
+        assert_eq!(age, CuDuration::from_millis(200));
+        assert!(age > max_age);
+        // Ranges are judged by their end; no tov means nothing to check.
+        let range = Tov::Range(CuTimeRange {
+            start: CuTime::from_millis(700),
+            end: CuTime::from_millis(980),
+        });
+        assert_eq!(command_age(&range, now), Some(CuDuration::from_millis(20)));
+        assert_eq!(command_age(&Tov::None, now), None);
+        // A tov in the future is not stale.
+        let future = Tov::Time(CuTime::from_millis(1_010));
+        assert_eq!(command_age(&future, now), Some(CuDuration::from_nanos(0)));
+    }
+
+    #[test]
+    fn untimestamped_goal_is_refused_by_the_age_guard() {
+        let mut cfg = servo_config(&[1]);
+        cfg.set("max_command_age_ms", 50u64);
+        let (mut bridge, _bus) = test_bridge(cfg, true, false);
+        let (ctx, clock) = CuContext::new_mock_clock();
+        clock.set_value(1_000_000_000);
+        let mut goal = JointPositions::new();
+        goal.fill_from_iter([100.0f32]);
+        let mut msg = CuMsg::new(Some(goal));
+
+        // No tov: refused and reported.
+        bridge
+            .send(&ctx, &TxChannels::GOAL_POSITIONS, &msg)
+            .unwrap();
+        assert!(bridge.pending.goal.is_none());
+        assert_eq!(bridge.refused_goals, 0);
+        // Refused again within the log interval: counted, not reported yet.
+        bridge
+            .send(&ctx, &TxChannels::GOAL_POSITIONS, &msg)
+            .unwrap();
+        assert!(bridge.pending.goal.is_none());
+        assert_eq!(bridge.refused_goals, 1);
+
+        // A fresh tov goes through.
+        msg.tov = Tov::Time(ctx.now());
+        bridge
+            .send(&ctx, &TxChannels::GOAL_POSITIONS, &msg)
+            .unwrap();
+        assert!(bridge.pending.goal.is_some());
+    }
+
+    #[test]
+    fn smoothing_filters_one_joint_and_bypasses_another() {
+        use crate::smoothing::JointSmoother;
+        let mut smoothers = [JointSmoother::new(0.5, 0.0), JointSmoother::bypass()];
+        // First goal seeds both filters.
+        assert_eq!(smoothers[0].apply(0.0), 0.0);
+        assert_eq!(smoothers[1].apply(0.0), 0.0);
+        // A step on both joints: the filtered joint lags, the bypassed one follows.
+        assert_eq!(smoothers[0].apply(10.0), 5.0);
+        assert_eq!(smoothers[1].apply(10.0), 10.0);
+        assert_eq!(smoothers[0].apply(10.0), 7.5);
+        assert_eq!(smoothers[1].apply(-3.0), -3.0);
+
+        // Slew limit caps the per-cycle change.
+        let mut slew = JointSmoother::new(1.0, 2.0);
+        assert_eq!(slew.apply(0.0), 0.0);
+        assert_eq!(slew.apply(10.0), 2.0);
+        assert_eq!(slew.apply(10.0), 4.0);
+        assert_eq!(slew.apply(3.0), 3.0);
+    }
+
+    #[test]
+    fn first_goal_is_slew_limited_from_present_position() {
+        let mut cfg = servo_config(&[1]);
+        cfg.set("max_step", 10.0f32);
+        let (mut bridge, mut bus) = test_bridge(cfg, true, false);
+        let (ctx, _clock) = CuContext::new_mock_clock();
+        let ack = status_packet(1, 0, &[]);
+
+        // The servo sits at 1000: a first goal of 3000 moves one step from there.
+        bus.write_all(&status_packet(1, 0, &1000u16.to_le_bytes()))
+            .unwrap();
+        assert_eq!(bridge.goal_to_raw(0, 3000.0).unwrap(), 1010);
+        assert_eq!(bridge.goal_to_raw(0, 3000.0).unwrap(), 1020);
+
+        // After an e-stop the filter restarts where the arm was held (1500).
+        bus.write_all(&ack).unwrap();
+        bridge.pending.estop = Some(true);
+        bridge.postprocess(&ctx).unwrap();
+        bus.write_all(&status_packet(1, 0, &1500u16.to_le_bytes()))
+            .unwrap();
+        bus.write_all(&ack).unwrap();
+        bridge.pending.estop = Some(false);
+        bridge.postprocess(&ctx).unwrap();
+        assert_eq!(bridge.goal_to_raw(0, 3000.0).unwrap(), 1510);
+
+        // Without any reading there is nothing to start from.
+        bridge.smoothers[0].reset();
+        bridge.position_known[0] = false;
+        drain(&mut bus);
+        assert!(bridge.goal_to_raw(0, 3000.0).is_err());
+    }
+
+    #[test]
+    fn home_deviation_detects_large_startup_offset() {
+        // Within tolerance on either side.
+        assert_eq!(home_deviation(2048, 2048, 100), None);
+        assert_eq!(home_deviation(2148, 2048, 100), None);
+        assert_eq!(home_deviation(1948, 2048, 100), None);
+        // Servo turned by ~90° while unpowered.
+        assert_eq!(home_deviation(3072, 2048, 100), Some(1024));
+        assert_eq!(home_deviation(1000, 2048, 100), Some(-1048));
+    }
+
+    #[test]
+    fn homing_detects_stop_from_load_spike() {
+        use crate::homing::StallDetector;
+        // Bit 10 carries the direction.
+        assert_eq!(decode_load(250), 250);
+        assert_eq!(decode_load(0x400 | 250), -250);
+
+        let mut detector = StallDetector::new(300, 3);
+        // Free motion, including a one-sample acceleration blip.
+        for load in [40, 60, 450, 55, 50] {
+            assert!(!detector.observe(load));
+        }
+        // Hitting the stop: load climbs and stays high (either direction).
+        assert!(!detector.observe(320));
+        assert!(!detector.observe(-480));
+        assert!(detector.observe(510));
+    }
+
+    #[test]
+    fn homing_limits_torque_and_restores_it_on_failure() {
+        let mut cfg = servo_config(&[1]);
+        cfg.set("homing0", "max".to_string());
+        cfg.set("homing_torque0", 400u16);
+        let (mut bridge, mut bus) = test_bridge(cfg, true, false);
+        let (ctx, _clock) = CuContext::new_mock_clock();
+        let homing = bridge.homing[0].unwrap();
+        let ack = status_packet(1, 0, &[]);
+        let write = |address: u8, value: u16| {
+            let [lo, hi] = value.to_le_bytes();
+            packet(1, instr::WRITE, &[address, lo, hi])
+        };
+
+        // Torque limit 1000; three samples at load 350 confirm the stop at 4000.
+        bus.write_all(&status_packet(1, 0, &1000u16.to_le_bytes()))
+            .unwrap();
+        for _ in 0..4 {
+            bus.write_all(&ack).unwrap();
+        }
+        for _ in 0..3 {
+            bus.write_all(&status_packet(1, 0, &350u16.to_le_bytes()))
+                .unwrap();
+        }
+        bus.write_all(&status_packet(1, 0, &4000u16.to_le_bytes()))
+            .unwrap();
+        // The hold write is not acknowledged: speed and torque limit are
+        // restored anyway, and the error is reported.
+        let mut corrupt = ack.clone();
+        *corrupt.last_mut().unwrap() ^= 0xFF;
+        bus.write_all(&corrupt).unwrap();
+        bus.write_all(&ack).unwrap();
+        bus.write_all(&ack).unwrap();
+        let err = bridge.home_servo(&ctx, 0, homing).unwrap_err();
+        assert!(err.to_string().contains("homing hold"), "{err}");
+
+        let load_read = packet(1, instr::READ, &[reg::PRESENT_LOAD, 2]);
+        let expected = [
+            packet(1, instr::READ, &[reg::TORQUE_LIMIT, 2]),
+            packet(1, instr::WRITE, &[reg::TORQUE_ENABLE, 1]),
+            write(reg::GOAL_SPEED, DEFAULT_HOMING_SPEED),
+            write(reg::TORQUE_LIMIT, 400),
+            write(reg::GOAL_POSITION, 4095),
+            load_read.clone(),
+            load_read.clone(),
+            load_read,
+            packet(1, instr::READ, &[reg::PRESENT_POSITION, 2]),
+            write(reg::GOAL_POSITION, 4000),
+            write(reg::GOAL_SPEED, 0),
+            write(reg::TORQUE_LIMIT, 1000),
+        ]
+        .concat();
+        assert_eq!(drain(&mut bus), expected);
     }
 
     #[test]
-    fn units_raw_roundtrip() {
-        use crate::calibration::Units;
-        let u = Units::Raw;
-        let tpr = 4096.0;
-        assert_eq!(u.from_raw(2048, 0.0, tpr), 2048.0);
-        assert_eq!(u.to_raw(2048.0, 0.0, tpr), 2048);
+    fn failed_jog_still_clears_speed_and_torque() {
+        // Follower mode: torque must be off again after the check.
+        let (mut bridge, mut bus) = test_bridge(servo_config(&[1]), false, false);
+        let ctx = CuContext::new_with_clock();
+        let ack = status_packet(1, 0, &[]);
+        let write = |address: u8, value: u16| {
+            let [lo, hi] = value.to_le_bytes();
+            packet(1, instr::WRITE, &[address, lo, hi])
+        };
+
+        // The start position and torque-on are acknowledged, the jog speed
+        // is not, so the jog fails right after torque was enabled.
+        bus.write_all(&status_packet(1, 0, &2048u16.to_le_bytes()))
+            .unwrap();
+        bus.write_all(&ack).unwrap();
+        assert!(bridge.jog_servo(&ctx, 0).is_err());
+
+        let expected = [
+            packet(1, instr::READ, &[reg::PRESENT_POSITION, 2]),
+            packet(1, instr::WRITE, &[reg::TORQUE_ENABLE, 1]),
+            write(reg::GOAL_SPEED, DEFAULT_JOG_SPEED),
+            write(reg::GOAL_SPEED, 0),
+            packet(1, instr::WRITE, &[reg::TORQUE_ENABLE, 0]),
+        ]
+        .concat();
+        assert_eq!(drain(&mut bus), expected);
     }
 
     #[test]
-    fn units_deg_roundtrip() {
-        use crate::calibration::{DEFAULT_TICKS_PER_REV, Units};
-        let u = Units::Deg;
-        let center = 2048.0;
-        // Center should map to 0°.
-        assert!((u.from_raw(2048, center, DEFAULT_TICKS_PER_REV as f32)).abs() < 1e-6);
-        // Converting 0° back should give the center tick.
-        assert_eq!(u.to_raw(0.0, center, DEFAULT_TICKS_PER_REV as f32), 2048);
+    fn jog_near_the_top_of_the_range_goes_backward_first() {
+        let (mut bridge, mut bus) = test_bridge(servo_config(&[1]), true, false);
+        // A real clock, so a missed reply times out instead of hanging.
+        let ctx = CuContext::new_with_clock();
+        let ack = status_packet(1, 0, &[]);
+        let position = |raw: u16| status_packet(1, 0, &raw.to_le_bytes());
+        let write = |address: u8, value: u16| {
+            let [lo, hi] = value.to_le_bytes();
+            packet(1, instr::WRITE, &[address, lo, hi])
+        };
+
+        // Starts at 4090: a forward jog of 100 would leave the range.
+        bus.write_all(&position(4090)).unwrap();
+        bus.write_all(&ack).unwrap();
+        bus.write_all(&ack).unwrap();
+        for reached in [3992, 4094, 4090] {
+            bus.write_all(&ack).unwrap();
+            bus.write_all(&position(reached)).unwrap();
+        }
+        bus.write_all(&ack).unwrap();
+        let result = bridge.jog_servo(&ctx, 0).unwrap();
+        assert!(result.passed(), "{result:?}");
+
+        let read = packet(1, instr::READ, &[reg::PRESENT_POSITION, 2]);
+        let expected = [
+            read.clone(),
+            packet(1, instr::WRITE, &[reg::TORQUE_ENABLE, 1]),
+            write(reg::GOAL_SPEED, DEFAULT_JOG_SPEED),
+            write(reg::GOAL_POSITION, 3990),
+            read.clone(),
+            write(reg::GOAL_POSITION, 4095),
+            read.clone(),
+            write(reg::GOAL_POSITION, 4090),
+            read,
+            write(reg::GOAL_SPEED, 0),
+        ]
+        .concat();
+        assert_eq!(drain(&mut bus), expected);
     }
 
     #[test]
-    fn units_rad_roundtrip() {
-        use crate::calibration::{DEFAULT_TICKS_PER_REV, Units};
-        let u = Units::Rad;
-        let center = 2048.0;
-        let rad = u.from_raw(3072, center, DEFAULT_TICKS_PER_REV as f32);
-        let back = u.to_raw(rad, center, DEFAULT_TICKS_PER_REV as f32);
-        assert_eq!(back, 3072);
+    fn sequence_increments_per_cycle_and_survives_freeze() {
+        let (mut bridge, _bus) = test_bridge(servo_config(&[1]), false, true);
+        let (ctx, _clock) = CuContext::new_mock_clock();
+        let mut msg = CuMsg::<JointPositions>::new(None);
+        for expected in 1..=3u64 {
+            bridge.preprocess(&ctx).unwrap();
+            bridge
+                .receive(&ctx, &RxChannels::POSITIONS, &mut msg)
+                .unwrap();
+            assert_eq!(msg.payload().unwrap().seq, expected);
+        }
+
+        let bytes = encode_to_vec(BincodeAdapter(&bridge), standard()).unwrap();
+        let (mut restored, _bus) = test_bridge(servo_config(&[1]), false, true);
+        let mut decoder = DecoderImpl::new(SliceReader::new(&bytes), standard(), ());
+        restored.thaw(&mut decoder).unwrap();
+
+        restored.preprocess(&ctx).unwrap();
+        restored
+            .receive(&ctx, &RxChannels::POSITIONS, &mut msg)
+            .unwrap();
+        assert_eq!(msg.payload().unwrap().seq, 4);
+    }
+
+    /// Status packet a servo sends back for a successful READ of `data`.
+    fn status_packet(id: u8, error: u8, data: &[u8]) -> Vec<u8> {
+        let mut packet = vec![0xFF, 0xFF, id, data.len() as u8 + 2, error];
+        packet.extend_from_slice(data);
+        packet.push(compute_checksum(&packet[2..]));
+        packet
+    }
+
+    /// Instruction packet the bridge sends to servo `id`.
+    fn packet(id: u8, instruction: u8, params: &[u8]) -> Vec<u8> {
+        let mut packet = vec![0xFF, 0xFF, id, params.len() as u8 + 2, instruction];
+        packet.extend_from_slice(params);
+        packet.push(compute_checksum(&packet[2..]));
+        packet
+    }
+
+    /// Everything the bridge wrote to the bus since the last call.
+    fn drain(bus: &mut TTYPort) -> Vec<u8> {
+        let mut sent = Vec::new();
+        let mut buf = [0u8; 64];
+        while let Ok(n) = bus.read(&mut buf) {
+            sent.extend_from_slice(&buf[..n]);
+        }
+        sent
     }
 
     #[test]
-    fn units_normalize_roundtrip() {
+    fn stuck_reading_is_flagged_and_survives_freeze() {
+        let mut cfg = servo_config(&[1, 2]);
+        cfg.set("stuck_cycles", 3u32);
+        let (mut bridge, mut bus) = test_bridge(cfg, true, true);
+        let (ctx, _clock) = CuContext::new_mock_clock();
+
+        // Command both servos far from where they sit.
+        let mut goals = JointPositions::new();
+        goals.fill_from_iter([3000.0f32, 3000.0]);
+        bridge.sync_write_positions(&goals).unwrap();
+        // Servo 1 keeps reporting 1000; servo 2 is moving toward its goal.
+        for cycle in 0..4u16 {
+            bus.write_all(&status_packet(1, 0, &1000u16.to_le_bytes()))
+                .unwrap();
+            bus.write_all(&status_packet(2, 0, &(1000 + cycle * 100).to_le_bytes()))
+                .unwrap();
+            bridge.preprocess(&ctx).unwrap();
+        }
+        let health = bridge.health();
+        assert!(health[0].stuck);
+        assert!(!health[1].stuck);
+
+        let bytes = encode_to_vec(BincodeAdapter(&bridge), standard()).unwrap();
+        let mut cfg = servo_config(&[1, 2]);
+        cfg.set("stuck_cycles", 3u32);
+        let (mut restored, _bus) = test_bridge(cfg, true, true);
+        let mut decoder = DecoderImpl::new(SliceReader::new(&bytes), standard(), ());
+        restored.thaw(&mut decoder).unwrap();
+        assert!(restored.health()[0].stuck);
+
+        // The flag clears as soon as the reading moves.
+        let detector = restored.stuck_detector.unwrap();
+        assert!(!detector.observe(&mut restored.stuck[0], 1001));
+    }
+
+    #[test]
+    fn stuck_detector_ignores_small_commanded_delta() {
+        let detector = StuckDetector::new(2, 20);
+        let mut state = StuckState {
+            goal: Some(1010),
+            ..Default::default()
+        };
+        for _ in 0..5 {
+            assert!(!detector.observe(&mut state, 1000));
+        }
+    }
+
+    #[test]
+    fn auto_calibration_saves_atomically_and_only_when_changed() {
+        use crate::calibration::AutoCalibration;
+
+        let dir = TempDir::new("autocal");
+        let path = dir.path("calibration.json");
+        let mut auto = AutoCalibration::new(
+            CalibrationData::default(),
+            path.clone(),
+            Some(CuDuration::from_millis(100)),
+        );
+        let t = CuTime::from_nanos;
+
+        for raw in [2000, 2000, 2000, 2100, 2104, 2100] {
+            auto.observe(1, raw);
+        }
+        // The first call starts the interval, the write happens once it elapsed.
+        assert!(!auto.save_if_due(t(0)).unwrap());
+        assert!(!auto.save_if_due(t(50_000_000)).unwrap());
+        assert!(auto.save_if_due(t(100_000_000)).unwrap());
+        // Saved on the writer thread.
+        auto.flush().unwrap();
+        assert_eq!(&CalibrationData::load(&path).unwrap(), auto.data());
+        assert_eq!(auto.data().servos[0].min, 2000);
+        assert_eq!(auto.data().servos[0].max, 2100);
+        // Written via a temporary file that is renamed into place.
+        assert!(!dir.path("calibration.json.tmp").exists());
+
+        // Readings inside the known range change nothing: no rewrite.
+        std::fs::remove_file(&path).unwrap();
+        auto.observe(1, 2050);
+        assert!(!auto.is_dirty());
+        assert!(!auto.save_if_due(t(300_000_000)).unwrap());
+        assert!(!auto.save_if_changed(t(400_000_000)).unwrap());
+        assert!(!path.exists());
+
+        // A single glitch or disagreeing reads do not widen the range.
+        for raw in [4000, 2050, 1500, 1900, 1700] {
+            auto.observe(1, raw);
+        }
+        assert!(!auto.is_dirty());
+
+        // Agreeing reads widen it up to the least extreme of them; saved on shutdown.
+        for raw in [1902, 1900, 1905] {
+            auto.observe(1, raw);
+        }
+        assert!(auto.save_if_changed(t(450_000_000)).unwrap());
+        assert_eq!(CalibrationData::load(&path).unwrap().servos[0].min, 1905);
+    }
+
+    #[test]
+    fn jog_self_check_evaluates_direction_and_magnitude() {
+        use crate::self_check::{JogVerdict, evaluate_jog};
+
+        // Followed the jog within tolerance, both ways.
+        assert_eq!(evaluate_jog(2000, 2100, Some(2095), 30), JogVerdict::Pass);
+        assert_eq!(evaluate_jog(2095, 1900, Some(1910), 30), JogVerdict::Pass);
+        // Frozen feedback or a dead motor.
+        assert_eq!(
+            evaluate_jog(2000, 2100, Some(2010), 30),
+            JogVerdict::NoResponse
+        );
+        assert_eq!(evaluate_jog(2000, 2100, None, 30), JogVerdict::NoFeedback);
+        // Inverted direction.
+        assert_eq!(
+            evaluate_jog(2000, 2100, Some(1900), 30),
+            JogVerdict::WrongDirection
+        );
+        // Right way, wrong amount.
+        assert_eq!(
+            evaluate_jog(2000, 2100, Some(2050), 30),
+            JogVerdict::WrongMagnitude
+        );
+        assert_eq!(
+            evaluate_jog(2000, 2100, Some(2200), 30),
+            JogVerdict::WrongMagnitude
+        );
+        // A jog clamped at the bottom of the range is judged by what was commanded.
+        assert_eq!(evaluate_jog(40, 0, Some(2), 30), JogVerdict::Pass);
+    }
+
+    #[test]
+    fn estop_wins_over_simultaneous_goal() {
+        use crate::commands::{GoalCommand, PendingCommands, ResolvedCommand};
+
+        let mut goal = JointPositions::new();
+        goal.fill_from_iter([100.0f32, 200.0]);
+        let mut pending = PendingCommands::default();
+        let mut estopped = false;
+
+        // A goal alone goes through.
+        pending.goal = Some(GoalCommand::Positions(goal.clone()));
+        assert!(matches!(
+            pending.resolve(&mut estopped),
+            ResolvedCommand::Goal(GoalCommand::Positions(p)) if p.as_slice() == goal.as_slice()
+        ));
+        assert!(pending.goal.is_none() && pending.estop.is_none());
+
+        // E-stop and goal in the same cycle: e-stop wins and latches.
+        pending.goal = Some(GoalCommand::Positions(goal.clone()));
+        pending.estop = Some(true);
+        assert!(matches!(
+            pending.resolve(&mut estopped),
+            ResolvedCommand::EStop
+        ));
+        assert!(estopped);
+
+        // Engaging again while latched is not a new e-stop.
+        pending.estop = Some(true);
+        assert!(matches!(
+            pending.resolve(&mut estopped),
+            ResolvedCommand::Idle
+        ));
+        assert!(estopped);
+
+        // A lingering goal (or stale-command hold) is ignored while latched.
+        pending.goal = Some(GoalCommand::Positions(goal.clone()));
+        assert!(matches!(
+            pending.resolve(&mut estopped),
+            ResolvedCommand::Idle
+        ));
+        pending.goal = Some(GoalCommand::HoldPresent);
+        assert!(matches!(
+            pending.resolve(&mut estopped),
+            ResolvedCommand::Idle
+        ));
+
+        // Release beats a goal in the same cycle; motion resumes on the next one.
+        pending.goal = Some(GoalCommand::Positions(goal.clone()));
+        pending.estop = Some(false);
+        assert!(matches!(
+            pending.resolve(&mut estopped),
+            ResolvedCommand::Release
+        ));
+        assert!(!estopped);
+        pending.goal = Some(GoalCommand::Positions(goal));
+        assert!(matches!(
+            pending.resolve(&mut estopped),
+            ResolvedCommand::Goal(GoalCommand::Positions(_))
+        ));
+    }
+
+    #[test]
+    fn estop_channel_cuts_torque_and_blocks_goals() {
+        let (mut bridge, mut bus) = test_bridge(servo_config(&[1, 2]), true, false);
+        let (ctx, _clock) = CuContext::new_mock_clock();
+        // Torque-off writes are acknowledged by each servo.
+        bus.write_all(&status_packet(1, 0, &[])).unwrap();
+        bus.write_all(&status_packet(2, 0, &[])).unwrap();
+
+        let mut goal_msg = CuMsg::<JointPositions>::new(None);
+        let mut goal = JointPositions::new();
+        goal.fill_from_iter([100.0f32, 200.0]);
+        goal_msg.set_payload(goal);
+        let estop_msg = CuMsg::new(Some(EStop { engaged: true }));
+        bridge
+            .send(&ctx, &TxChannels::GOAL_POSITIONS, &goal_msg)
+            .unwrap();
+        bridge.send(&ctx, &TxChannels::ESTOP, &estop_msg).unwrap();
+        bridge.postprocess(&ctx).unwrap();
+        assert!(bridge.is_estopped());
+        let refused = bridge.write_group_positions("arm", &[0.0]).unwrap_err();
+        assert!(refused.to_string().contains("e-stopped"));
+
+        // Only the two torque-off writes went out, no goal sync-write.
+        let torque_off = |id: u8| packet(id, instr::WRITE, &[reg::TORQUE_ENABLE, 0]);
+        assert_eq!(drain(&mut bus), [torque_off(1), torque_off(2)].concat());
+
+        // An engage that keeps arriving while latched sends nothing more.
+        bridge.send(&ctx, &TxChannels::ESTOP, &estop_msg).unwrap();
+        bridge.postprocess(&ctx).unwrap();
+        assert!(bridge.is_estopped());
+        assert!(drain(&mut bus).is_empty());
+    }
+
+    #[test]
+    fn pending_commands_survive_freeze() {
+        let (mut bridge, _bus) = test_bridge(servo_config(&[1]), true, false);
+        let (ctx, _clock) = CuContext::new_mock_clock();
+        let mut goal = JointPositions::new();
+        goal.fill_from_iter([100.0f32]);
+        bridge
+            .send(&ctx, &TxChannels::GOAL_POSITIONS, &CuMsg::new(Some(goal)))
+            .unwrap();
+        let estop_msg = CuMsg::new(Some(EStop { engaged: true }));
+        bridge.send(&ctx, &TxChannels::ESTOP, &estop_msg).unwrap();
+
+        // Frozen between send and postprocess.
+        let bytes = encode_to_vec(BincodeAdapter(&bridge), standard()).unwrap();
+        let (mut restored, _bus) = test_bridge(servo_config(&[1]), true, false);
+        let mut decoder = DecoderImpl::new(SliceReader::new(&bytes), standard(), ());
+        restored.thaw(&mut decoder).unwrap();
+        assert_eq!(restored.pending.estop, Some(true));
+        let Some(GoalCommand::Positions(positions)) = &restored.pending.goal else {
+            panic!("goal lost: {:?}", restored.pending.goal);
+        };
+        assert_eq!(positions.as_slice(), [100.0]);
+
+        // A queued group goal keeps its slots.
+        let mut cfg = servo_config(&[1, 2]);
+        cfg.0.insert(
+            "groups".to_string(),
+            serde_json::from_str(r#"{"wrist": [2]}"#).unwrap(),
+        );
+        let (mut bridge, _bus) = test_bridge(cfg.clone(), true, false);
+        bridge.write_group_positions("wrist", &[7.0]).unwrap();
+        let bytes = encode_to_vec(BincodeAdapter(&bridge), standard()).unwrap();
+        let (mut restored, _bus) = test_bridge(cfg, true, false);
+        let mut decoder = DecoderImpl::new(SliceReader::new(&bytes), standard(), ());
+        restored.thaw(&mut decoder).unwrap();
+        let Some(GoalCommand::Group { slots, positions }) = &restored.pending.goal else {
+            panic!("group goal lost: {:?}", restored.pending.goal);
+        };
+        assert_eq!(slots.as_slice(), [1]);
+        assert_eq!(positions.as_slice(), [7.0]);
+    }
+
+    #[test]
+    fn flight_recorder_dumps_each_fault_once() {
+        let dir = TempDir::new("recorder-faults");
+        for depth in [1u32, 2, 3, 5] {
+            let path = dir.path(&format!("depth{depth}.csv"));
+            let mut cfg = servo_config(&[1, 2]);
+            cfg.set("flight_recorder_depth", depth);
+            cfg.set("flight_recorder_file", path.display().to_string());
+            let (mut bridge, _bus) = test_bridge(cfg, false, true);
+            let dumps = || {
+                std::fs::read_to_string(&path)
+                    .unwrap_or_default()
+                    .matches("# fault:")
+                    .count()
+            };
+
+            // Servo 1 stays dead: one dump when it misses its third read.
+            let mut failed = [false; MAX_SERVOS];
+            failed[0] = true;
+            for cycle in 1..=8 {
+                bridge.record_cycle(failed);
+                assert_eq!(dumps(), usize::from(cycle >= 3), "depth {depth}");
+            }
+
+            // Servo 2 overloads: one dump while the flag stays set, another
+            // once it clears and comes back.
+            for (overloaded, expected) in [(true, 2), (true, 2), (false, 2), (true, 3)] {
+                bridge.servo_errors[1] = if overloaded {
+                    ServoError::OVERLOAD
+                } else {
+                    ServoError::default()
+                };
+                bridge.record_cycle(failed);
+                assert_eq!(dumps(), expected, "depth {depth}");
+            }
+            assert!(
+                std::fs::read_to_string(&path)
+                    .unwrap()
+                    .contains("# fault: servo 2 overloaded")
+            );
+        }
+    }
+
+    #[test]
+    fn flight_recorder_wraps_and_dumps_last_entries() {
+        use crate::recorder::{FlightRecorder, RecorderEntry};
+
+        let mut recorder = FlightRecorder::new(3);
+        for seq in 1..=5u64 {
+            let mut raw = [0u16; MAX_SERVOS];
+            raw[0] = 2000 + seq as u16;
+            raw[1] = 1000;
+            let mut failed = [false; MAX_SERVOS];
+            failed[1] = seq >= 4;
+            recorder.record(RecorderEntry {
+                seq,
+                time: CuTime::from_nanos(seq * 10_000_000),
+                raw,
+                failed,
+            });
+        }
+        assert_eq!(
+            recorder.entries().map(|e| e.seq).collect::<Vec<_>>(),
+            [3, 4, 5]
+        );
+        assert_eq!(recorder.consecutive_failures(0), 0);
+        assert_eq!(recorder.consecutive_failures(1), 2);
+
+        let dir = TempDir::new("recorder");
+        let path = dir.path("recorder.csv");
+        recorder.dump(&path, &[1, 2], "e-stop engaged").unwrap();
+        let dump = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            dump,
+            "# fault: e-stop engaged\n\
+             seq,time_ns,servo1,servo2\n\
+             3,30000000,2003,1000\n\
+             4,40000000,2004,ERR\n\
+             5,50000000,2005,ERR\n"
+        );
+    }
+
+    #[test]
+    fn raw_speed_maps_to_normalized_rate() {
         use crate::calibration::Units;
-        let u = Units::Normalize;
+
+        // Sign in bit 15, magnitude in steps/s.
+        assert_eq!(decode_speed(200), 200);
+        assert_eq!(decode_speed(0x8000 | 200), -200);
+
+        // half_range 1000 ticks: 200 steps/s is a fifth of the half range per second.
+        let half_range = 1000.0;
+        let rate = Units::Normalize.rate_from_raw(decode_speed(200) as f32, half_range);
+        assert!((rate - 0.2).abs() < 1e-6);
+        let rate = Units::Normalize.rate_from_raw(decode_speed(0x8000 | 500) as f32, half_range);
+        assert!((rate + 0.5).abs() < 1e-6);
+
+        // Consistent with the normalized position: moving `rate` for one second
+        // from center lands on the position `200` ticks past center.
         let center = 2048.0;
-        let half_range = 1024.0; // min=1024, max=3072
-        assert!((u.from_raw(2048, center, half_range)).abs() < 1e-6);
-        assert!((u.from_raw(1024, center, half_range) + 1.0).abs() < 1e-6);
-        assert!((u.from_raw(3072, center, half_range) - 1.0).abs() < 1e-6);
-        assert_eq!(u.to_raw(0.0, center, half_range), 2048);
-        assert_eq!(u.to_raw(-1.0, center, half_range), 1024);
-        assert_eq!(u.to_raw(1.0, center, half_range), 3072);
+        let pos = Units::Normalize.from_raw(2248, center, half_range);
+        assert!((pos - 0.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn overheated_servo_alone_is_skipped() {
+        let mut cfg = servo_config(&[1, 2]);
+        // `ComponentConfig::set` only takes scalars; lists come in as parsed values.
+        cfg.0.insert(
+            "skip_write_on".to_string(),
+            serde_json::from_str(r#"["overheat"]"#).unwrap(),
+        );
+        let (mut bridge, mut bus) = test_bridge(cfg, true, true);
+        let (ctx, _clock) = CuContext::new_mock_clock();
+        let mut goals = JointPositions::new();
+        goals.fill_from_iter([1500.0f32, 2500.0]);
+
+        // Servo 2 answers with the overheat flag set.
+        bus.write_all(&status_packet(1, 0, &2000u16.to_le_bytes()))
+            .unwrap();
+        bus.write_all(&status_packet(2, 0x04, &2000u16.to_le_bytes()))
+            .unwrap();
+        bridge.preprocess(&ctx).unwrap();
+        let health = bridge.health();
+        assert!(!health[0].skipped);
+        assert!(health[1].skipped);
+        assert_eq!(health[1].errors, ServoError::OVERHEAT);
+
+        drain(&mut bus);
+        bridge.sync_write_positions(&goals).unwrap();
+        let mut params = [0u8; MAX_PACKET_SIZE - 5];
+        let n = build_goal_sync_write(&[(1, 1500)], &mut params).unwrap();
+        let expected = packet(BROADCAST_ID, instr::SYNC_WRITE, &params[..n]);
+        assert_eq!(drain(&mut bus), expected);
+
+        // Once the flag clears, servo 2 is written again.
+        bus.write_all(&status_packet(1, 0, &2000u16.to_le_bytes()))
+            .unwrap();
+        bus.write_all(&status_packet(2, 0, &2000u16.to_le_bytes()))
+            .unwrap();
+        bridge.preprocess(&ctx).unwrap();
+        assert!(!bridge.health()[1].skipped);
+        drain(&mut bus);
+        bridge.sync_write_positions(&goals).unwrap();
+        let n = build_goal_sync_write(&[(1, 1500), (2, 2500)], &mut params).unwrap();
+        let expected = packet(BROADCAST_ID, instr::SYNC_WRITE, &params[..n]);
+        assert_eq!(drain(&mut bus), expected);
+    }
+
+    #[test]
+    fn diagnostics_read_on_configured_phase() {
+        use crate::health::diagnostics_due;
+
+        let due: Vec<u64> = (1..=25).filter(|&c| diagnostics_due(c, 10, 3)).collect();
+        assert_eq!(due, [3, 13, 23]);
+        let due: Vec<u64> = (1..=25).filter(|&c| diagnostics_due(c, 10, 0)).collect();
+        assert_eq!(due, [10, 20]);
+        assert!(!(0..100).any(|c| diagnostics_due(c, 0, 0)));
+
+        // Through the bridge: servo 1 answers a position read every cycle and a
+        // diagnostics read only on cycle 2 of every 3.
+        let mut cfg = servo_config(&[1]);
+        cfg.set("diagnostics_interval", 3u32);
+        cfg.set("diagnostics_phase", 2u32);
+        let (mut bridge, mut bus) = test_bridge(cfg, false, true);
+        let (ctx, _clock) = CuContext::new_mock_clock();
+        for cycle in 1..=3u8 {
+            bus.write_all(&status_packet(1, 0, &2048u16.to_le_bytes()))
+                .unwrap();
+            if cycle == 2 {
+                // Load 100, 12.0 V, 35 °C.
+                bus.write_all(&status_packet(1, 0, &[100, 0, 120, 35]))
+                    .unwrap();
+            }
+            bridge.preprocess(&ctx).unwrap();
+            let diag = bridge.health()[0].diagnostics;
+            if cycle < 2 {
+                assert_eq!(diag, None);
+            } else {
+                assert_eq!(
+                    diag,
+                    Some(crate::health::ServoDiagnostics {
+                        load: 100,
+                        voltage: 120,
+                        temperature: 35,
+                    })
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn missed_sync_write_is_retried_individually() {
+        let mut cfg = servo_config(&[1, 2]);
+        cfg.0.insert(
+            "verify_sync_write".to_string(),
+            serde_json::from_str("true").unwrap(),
+        );
+        let (mut bridge, mut bus) = test_bridge(cfg, true, false);
+        let mut goals = JointPositions::new();
+        goals.fill_from_iter([1500.0f32, 2500.0]);
+
+        // Servo 1 read back its new goal; servo 2 still holds its old one,
+        // then acknowledges the individual re-write.
+        drain(&mut bus);
+        bus.write_all(&status_packet(1, 0, &1500u16.to_le_bytes()))
+            .unwrap();
+        bus.write_all(&status_packet(2, 0, &2000u16.to_le_bytes()))
+            .unwrap();
+        bus.write_all(&status_packet(2, 0, &[])).unwrap();
+        bridge.sync_write_positions(&goals).unwrap();
+
+        let mut params = [0u8; MAX_PACKET_SIZE - 5];
+        let n = build_goal_sync_write(&[(1, 1500), (2, 2500)], &mut params).unwrap();
+        let mut expected = packet(BROADCAST_ID, instr::SYNC_WRITE, &params[..n]);
+        expected.extend(packet(1, instr::READ, &[reg::GOAL_POSITION, 2]));
+        expected.extend(packet(2, instr::READ, &[reg::GOAL_POSITION, 2]));
+        expected.extend(packet(2, instr::WRITE, &[reg::GOAL_POSITION, 0xC4, 0x09]));
+        assert_eq!(drain(&mut bus), expected);
+    }
+
+    #[test]
+    fn unlatched_reg_write_aborts_action() {
+        let mut cfg = servo_config(&[1, 2]);
+        cfg.set("goal_write", "reg_write".to_string());
+        cfg.set("verify_reg_write", "abort".to_string());
+        let (mut bridge, mut bus) = test_bridge(cfg, true, false);
+        let action = packet(BROADCAST_ID, instr::ACTION, &[]);
+        let mut goals = JointPositions::new();
+        goals.fill_from_iter([1500.0f32, 2500.0]);
+
+        // Both acknowledge, but servo 2's async-write flag reads 0.
+        drain(&mut bus);
+        bus.write_all(&status_packet(1, 0, &[])).unwrap();
+        bus.write_all(&status_packet(2, 0, &[])).unwrap();
+        bus.write_all(&status_packet(1, 0, &[1])).unwrap();
+        bus.write_all(&status_packet(2, 0, &[0])).unwrap();
+        assert!(bridge.sync_write_positions(&goals).is_err());
+        let mut expected = packet(1, instr::REG_WRITE, &[reg::GOAL_POSITION, 0xDC, 0x05]);
+        expected.extend(packet(
+            2,
+            instr::REG_WRITE,
+            &[reg::GOAL_POSITION, 0xC4, 0x09],
+        ));
+        expected.extend(packet(1, instr::READ, &[reg::ASYNC_WRITE_FLAG, 1]));
+        expected.extend(packet(2, instr::READ, &[reg::ASYNC_WRITE_FLAG, 1]));
+        assert_eq!(drain(&mut bus), expected);
+
+        // Once both latch, ACTION follows the checks.
+        bus.write_all(&status_packet(1, 0, &[])).unwrap();
+        bus.write_all(&status_packet(2, 0, &[])).unwrap();
+        bus.write_all(&status_packet(1, 0, &[1])).unwrap();
+        bus.write_all(&status_packet(2, 0, &[1])).unwrap();
+        bridge.sync_write_positions(&goals).unwrap();
+        expected.extend(&action);
+        assert_eq!(drain(&mut bus), expected);
+    }
+
+    #[test]
+    fn missing_reg_write_ack_is_tracked_per_servo() {
+        let mut cfg = servo_config(&[1, 2]);
+        cfg.set("goal_write", "reg_write".to_string());
+        let (mut bridge, mut bus) = test_bridge(cfg, true, false);
+        let mut goals = JointPositions::new();
+        goals.fill_from_iter([1500.0f32, 2500.0]);
+        drain(&mut bus);
+
+        // Servo 2 does not answer: flagged once, ACTION still sent.
+        for _ in 0..2 {
+            bus.write_all(&status_packet(1, 0, &[])).unwrap();
+            bridge.sync_write_positions(&goals).unwrap();
+            assert_eq!(bridge.reg_write_unacked[..2], [false, true]);
+            assert!(drain(&mut bus).ends_with(&packet(BROADCAST_ID, instr::ACTION, &[])));
+        }
+
+        // It answers again: cleared, so the next outage is reported.
+        bus.write_all(&status_packet(1, 0, &[])).unwrap();
+        bus.write_all(&status_packet(2, 0, &[])).unwrap();
+        bridge.sync_write_positions(&goals).unwrap();
+        assert_eq!(bridge.reg_write_unacked[..2], [false, false]);
+    }
+
+    #[test]
+    fn startup_runs_in_order_and_aborts_with_context() {
+        use crate::startup::StartupState;
+        use std::sync::Mutex;
+
+        static LOG: Mutex<Vec<(&str, StartupState)>> = Mutex::new(Vec::new());
+        let mut hooks = StartupHooks::new();
+        hooks.register("order_first", |bridge, _| {
+            LOG.lock().unwrap().push(("first", bridge.startup_state()));
+            Ok(())
+        });
+        hooks.register("order_second", |bridge, _| {
+            LOG.lock().unwrap().push(("second", bridge.startup_state()));
+            Ok(())
+        });
+        let sequence = |steps: &str| {
+            let mut cfg = servo_config(&[1]);
+            cfg.0.insert(
+                "startup_sequence".to_string(),
+                serde_json::from_str(steps).unwrap(),
+            );
+            cfg
+        };
+        let (ctx, _clock) = CuContext::new_mock_clock();
+
+        let cfg = sequence(r#"["hook:order_first", "check_home", "hook:order_second", "torque"]"#);
+        let (mut bridge, _bus) = test_bridge_with_hooks(cfg, false, true, Some(hooks.clone()));
+        assert_eq!(
+            bridge
+                .startup_steps()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>(),
+            [
+                "hook:order_first",
+                "check_home",
+                "hook:order_second",
+                "torque"
+            ]
+        );
+        bridge.start(&ctx).unwrap();
+        assert_eq!(
+            *LOG.lock().unwrap(),
+            [
+                ("first", StartupState::Running(0)),
+                ("second", StartupState::Running(2)),
+            ]
+        );
+        assert_eq!(bridge.startup_state(), StartupState::Done);
+
+        // Servo 1 never answers the ping: the steps after it do not run.
+        LOG.lock().unwrap().clear();
+        let cfg = sequence(r#"["hook:order_first", "ping", "hook:order_second"]"#);
+        let (mut bridge, _bus) = test_bridge_with_hooks(cfg, false, true, Some(hooks));
+        let err = bridge.start(&ctx).unwrap_err().to_string();
+        assert!(err.contains("startup step \"ping\" failed"), "{err}");
+        assert!(err.contains("servo 1 did not answer"), "{err}");
+        assert_eq!(bridge.startup_state(), StartupState::Failed(1));
+        assert_eq!(*LOG.lock().unwrap(), [("first", StartupState::Running(0))]);
+
+        // Unknown steps, unregistered hooks and repeats are config errors, and
+        // so are configured steps left out or a conflicting self_check_on_start.
+        for (bad, extra) in [
+            (r#"["torque", "calibrate"]"#, None),
+            (r#"["hook:missing"]"#, None),
+            (r#"["torque", "torque"]"#, None),
+            (r#"["check_home", "torque"]"#, Some(("homing0", r#""max""#))),
+            (r#"["homing", "torque"]"#, Some(("home0", "2048"))),
+            (
+                r#"["self_check", "torque"]"#,
+                Some(("self_check_on_start", "true")),
+            ),
+        ] {
+            let mut cfg = sequence(bad);
+            if let Some((key, value)) = extra {
+                cfg.0
+                    .insert(key.to_string(), serde_json::from_str(value).unwrap());
+            }
+            let (_bus, port) = TTYPort::pair().expect("pty pair");
+            let resources = Resources {
+                serial: Owned(LinuxSerialPort::new(Box::new(port))),
+                startup_hooks: None,
+            };
+            assert!(
+                FeetechBridge::new(Some(&cfg), &[], &[], resources).is_err(),
+                "{bad} {extra:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn cycle_profile_matches_measured_callback_time() {
+        let mut cfg = servo_config(&[1, 2]);
+        cfg.set("profile_log_cycles", 2u32);
+        let (mut bridge, mut bus) = test_bridge(cfg, true, true);
+        // Real clock: the phases must measure actual time.
+        let ctx = CuContext::new_with_clock();
+        let mut goals = JointPositions::new();
+        goals.fill_from_iter([1500.0f32, 2500.0]);
+
+        // Servo 2 never answers, so each cycle waits out its read timeout.
+        // The profile of a cycle closes at the next preprocess.
+        let mut measured = Vec::new();
+        for _ in 0..3 {
+            bus.write_all(&status_packet(1, 0, &2048u16.to_le_bytes()))
+                .unwrap();
+            let started = std::time::Instant::now();
+            bridge.preprocess(&ctx).unwrap();
+            bridge.pending.goal = Some(GoalCommand::Positions(goals.clone()));
+            bridge.postprocess(&ctx).unwrap();
+            measured.push(started.elapsed());
+        }
+        let profile = bridge.cycle_profile().expect("profiled");
+        assert_eq!(profile.seq, 2);
+        assert_eq!(profile.phases_sum(), profile.total);
+
+        // The total is the callbacks' wall time, minus closing the previous
+        // cycle at the top of preprocess.
+        let total = Duration::from_nanos(profile.total.as_nanos());
+        assert!(total <= measured[1], "{total:?} > {:?}", measured[1]);
+        assert!(
+            measured[1] - total < Duration::from_millis(2),
+            "{total:?} vs {:?}",
+            measured[1]
+        );
+        assert!(profile.wait >= CuDuration::from_millis(5), "{profile:?}");
+        assert!(profile.write > CuDuration::default(), "{profile:?}");
+        assert!(profile.convert > CuDuration::default(), "{profile:?}");
+        assert!(profile.total > profile.wait);
+    }
+
+    #[test]
+    fn profile_channel_alone_does_not_poll_the_bus() {
+        let cfg = servo_config(&[1]);
+        let (mut bus, mut port) = TTYPort::pair().expect("pty pair");
+        port.set_timeout(Duration::from_millis(5))
+            .expect("pty timeout");
+        let resources = Resources {
+            serial: Owned(LinuxSerialPort::new(Box::new(port))),
+            startup_hooks: None,
+        };
+        let rx_channels = [BridgeChannelConfig::from_static(
+            &RxChannels::PROFILE,
+            None,
+            None,
+        )];
+        let mut bridge = FeetechBridge::new(Some(&cfg), &[], &rx_channels, resources).unwrap();
+        let ctx = CuContext::new_with_clock();
+
+        // Timed and numbered every cycle, but nothing sent.
+        for expected in [None, Some(1), Some(2)] {
+            bridge.preprocess(&ctx).unwrap();
+            assert_eq!(bridge.cycle_profile().map(|p| p.seq), expected);
+            bridge.postprocess(&ctx).unwrap();
+        }
+        assert!(drain(&mut bus).is_empty());
+    }
+
+    #[test]
+    fn nested_phase_counts_only_once() {
+        let (ctx, clock) = CuContext::new_mock_clock();
+        let mut profiler = Profiler::new(0);
+        let step = |ms| clock.increment(CuDuration::from_millis(ms));
+        let entered = profiler.enter(&ctx.clock);
+
+        // A 1 ms conversion around a 3 ms seed read (write + wait).
+        let convert = profiler.start().unwrap();
+        step(1);
+        let write = profiler.start().unwrap();
+        step(1);
+        profiler.add(Phase::Write, write);
+        let wait = profiler.start().unwrap();
+        step(2);
+        profiler.add(Phase::Wait, wait);
+        profiler.add(Phase::Convert, convert);
+        step(1);
+        profiler.leave(entered);
+        profiler.finish_cycle();
+
+        let profile = profiler.last().unwrap();
+        let ms = CuDuration::from_millis;
+        assert_eq!(profile.write, ms(1));
+        assert_eq!(profile.wait, ms(2));
+        assert_eq!(profile.convert, ms(1));
+        assert_eq!(profile.other, ms(1));
+        assert_eq!(profile.total, ms(5));
+        assert_eq!(profile.phases_sum(), profile.total);
+    }
+
+    #[test]
+    fn ready_gate_withholds_until_threshold() {
+        let mut gate = ReadyGate::new(3);
+        assert!(!gate.observe(true));
+        assert!(!gate.observe(true));
+        // A partial read resets the streak.
+        assert!(!gate.observe(false));
+        assert!(!gate.observe(true));
+        assert!(!gate.observe(true));
+        assert!(gate.observe(true));
+        // Once open, the gate stays open even through a partial read.
+        assert!(gate.observe(false));
+    }
+
+    #[test]
+    fn ready_gate_zero_threshold_is_open() {
+        let mut gate = ReadyGate::new(0);
+        assert!(gate.is_ready());
+        assert!(gate.observe(false));
+    }
+
+    #[test]
+    fn positions_are_withheld_until_ready() {
+        let mut cfg = servo_config(&[1]);
+        cfg.set("ready_after_cycles", 3u32);
+        let (mut bridge, mut bus) = test_bridge(cfg, false, true);
+        let (ctx, _clock) = CuContext::new_mock_clock();
+        let mut msg = CuMsg::<JointPositions>::new(None);
+        let mut cycle = |bridge: &mut FeetechBridge, answer: bool| {
+            if answer {
+                bus.write_all(&status_packet(1, 0, &2048u16.to_le_bytes()))
+                    .unwrap();
+            }
+            bridge.preprocess(&ctx).unwrap();
+            bridge
+                .receive(&ctx, &RxChannels::POSITIONS, &mut msg)
+                .unwrap();
+            msg.payload().is_some()
+        };
+
+        // Two full reads, then a failed one resets the count.
+        assert!(!cycle(&mut bridge, true));
+        assert!(!cycle(&mut bridge, true));
+        assert!(!cycle(&mut bridge, false));
+        // Published from the third consecutive full read on.
+        assert!(!cycle(&mut bridge, true));
+        assert!(!cycle(&mut bridge, true));
+        assert!(cycle(&mut bridge, true));
+        assert!(cycle(&mut bridge, true));
+    }
+
+    /// Poll one answered cycle and return the published positions.
+    fn poll_positions(
+        bridge: &mut FeetechBridge,
+        bus: &mut TTYPort,
+        ctx: &CuContext,
+    ) -> JointPositions {
+        bus.write_all(&status_packet(1, 0, &2048u16.to_le_bytes()))
+            .unwrap();
+        bridge.preprocess(ctx).unwrap();
+        let mut msg = CuMsg::<JointPositions>::new(None);
+        bridge
+            .receive(ctx, &RxChannels::POSITIONS, &mut msg)
+            .unwrap();
+        msg.payload().cloned().expect("positions published")
+    }
+
+    #[test]
+    fn robot_stamp_matches_the_read_time() {
+        let (mut bridge, mut bus) = test_bridge(servo_config(&[1]), false, true);
+        let (ctx, clock) = CuContext::new_mock_clock();
+        clock.set_value(1_500_000_000);
+        let positions = poll_positions(&mut bridge, &mut bus, &ctx);
+        assert_eq!(positions.stamp_ns, 1_500_000_000);
+        assert_eq!(positions.seq, 1);
+    }
+
+    #[test]
+    fn wall_stamp_is_unix_time_at_the_read() {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        let mut cfg = servo_config(&[1]);
+        cfg.set("stamp_clock", "wall".to_string());
+        let (mut bridge, mut bus) = test_bridge(cfg, false, true);
+        let (ctx, _clock) = CuContext::new_mock_clock();
+        let unix_ns = || {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos() as u64
+        };
+        let before = unix_ns();
+        let positions = poll_positions(&mut bridge, &mut bus, &ctx);
+        let after = unix_ns();
+        assert!((before..=after).contains(&positions.stamp_ns));
+    }
+
+    #[test]
+    fn ros2_stamp_follows_the_latest_clock_message() {
+        let mut cfg = servo_config(&[1]);
+        cfg.set("stamp_clock", "ros2".to_string());
+        let build = |tx: &[BridgeChannelConfig<TxId>]| {
+            let (bus, mut port) = TTYPort::pair().expect("pty pair");
+            port.set_timeout(Duration::from_millis(5))
+                .expect("pty timeout");
+            let resources = Resources {
+                serial: Owned(LinuxSerialPort::new(Box::new(port))),
+                startup_hooks: None,
+            };
+            let rx = [BridgeChannelConfig::from_static(
+                &RxChannels::POSITIONS,
+                None,
+                None,
+            )];
+            FeetechBridge::new(Some(&cfg), tx, &rx, resources).map(|bridge| (bridge, bus))
+        };
+        // Without the ros_clock channel there is nothing to stamp from.
+        assert!(build(&[]).is_err());
+        let tx = [BridgeChannelConfig::from_static(
+            &TxChannels::ROS_CLOCK,
+            None,
+            None,
+        )];
+        let (mut bridge, mut bus) = build(&tx).unwrap();
+        let (ctx, clock) = CuContext::new_mock_clock();
+
+        // No /clock yet: stamped 0.
+        clock.set_value(1_000_000_000);
+        assert_eq!(poll_positions(&mut bridge, &mut bus, &ctx).stamp_ns, 0);
+
+        // /clock at 100.000000005 s, read 2 ms of robot time later.
+        let clock_msg = CuMsg::new(Some(RosTime {
+            sec: 100,
+            nanosec: 5,
+        }));
+        bridge
+            .send(&ctx, &TxChannels::ROS_CLOCK, &clock_msg)
+            .unwrap();
+        clock.increment(CuDuration::from_millis(2));
+        assert_eq!(
+            poll_positions(&mut bridge, &mut bus, &ctx).stamp_ns,
+            100_002_000_005
+        );
+    }
+
+    /// Minimal Foxglove WebSocket client for the stream test.
+    struct FoxgloveClient(std::net::TcpStream);
+
+    impl FoxgloveClient {
+        fn connect(addr: SocketAddr) -> Self {
+            use crate::foxglove::SUBPROTOCOL;
+            let mut stream = std::net::TcpStream::connect(addr).unwrap();
+            stream
+                .set_read_timeout(Some(Duration::from_secs(2)))
+                .unwrap();
+            // Key and accept value from the RFC 6455 example.
+            write!(
+                stream,
+                "GET / HTTP/1.1\r\nHost: {addr}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+                 Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\
+                 Sec-WebSocket-Protocol: {SUBPROTOCOL}\r\n\r\n"
+            )
+            .unwrap();
+            let mut response = Vec::new();
+            let mut byte = [0u8];
+            while !response.ends_with(b"\r\n\r\n") {
+                stream.read_exact(&mut byte).unwrap();
+                response.push(byte[0]);
+            }
+            let response = String::from_utf8(response).unwrap();
+            assert!(response.starts_with("HTTP/1.1 101"), "{response}");
+            assert!(response.contains("Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo="));
+            Self(stream)
+        }
+
+        fn read(&mut self) -> (u8, Vec<u8>) {
+            crate::foxglove::read_frame(&mut self.0).unwrap()
+        }
+
+        fn read_json(&mut self) -> serde_json::Value {
+            let (opcode, payload) = self.read();
+            assert_eq!(opcode, 0x1);
+            serde_json::from_slice(&payload).unwrap()
+        }
+
+        /// Send a masked frame, as clients must.
+        fn send(&mut self, opcode: u8, payload: &[u8]) {
+            assert!(payload.len() < 126);
+            let mask = [0x12, 0x34, 0x56, 0x78];
+            let mut frame = vec![0x80 | opcode, 0x80 | payload.len() as u8];
+            frame.extend_from_slice(&mask);
+            frame.extend(payload.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+            self.0.write_all(&frame).unwrap();
+        }
+    }
+
+    /// Poll `done` until it holds, failing after a second.
+    fn wait_until(mut done: impl FnMut() -> bool) {
+        let deadline = std::time::Instant::now() + Duration::from_secs(1);
+        while !done() {
+            assert!(std::time::Instant::now() < deadline, "timed out");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn foxglove_client_receives_positions_and_diagnostics() {
+        use crate::foxglove::{
+            DIAGNOSTICS_TOPIC, DiagnosticsSample, POSITIONS_TOPIC, PositionsSample,
+        };
+
+        let mut cfg = servo_config(&[1]);
+        cfg.set("foxglove_port", 0u16);
+        // No Rx channel: the stream alone polls the bus.
+        let (mut bridge, mut bus) = test_bridge(cfg, false, false);
+        let (ctx, clock) = CuContext::new_mock_clock();
+        clock.set_value(42);
+        let addr = bridge.foxglove_addr().unwrap();
+        fn server(bridge: &FeetechBridge) -> &FoxgloveServer {
+            bridge.foxglove.as_ref().unwrap()
+        }
+
+        let mut client = FoxgloveClient::connect(addr);
+        assert_eq!(client.read_json()["op"], "serverInfo");
+        let advertise = client.read_json();
+        assert_eq!(advertise["op"], "advertise");
+        let channel = |topic: &str| {
+            let channel = advertise["channels"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|c| c["topic"] == topic)
+                .unwrap();
+            assert_eq!(channel["encoding"], "json");
+            // The advertised schema is valid JSON.
+            serde_json::from_str::<serde_json::Value>(channel["schema"].as_str().unwrap()).unwrap();
+            channel["id"].as_u64().unwrap()
+        };
+        let subscribe = format!(
+            r#"{{"op":"subscribe","subscriptions":[{{"id":7,"channelId":{}}},{{"id":8,"channelId":{}}}]}}"#,
+            channel(POSITIONS_TOPIC),
+            channel(DIAGNOSTICS_TOPIC)
+        );
+        client.send(0x1, subscribe.as_bytes());
+        wait_until(|| server(&bridge).wants_positions() && server(&bridge).wants_diagnostics());
+
+        bus.write_all(&status_packet(1, 0, &2048u16.to_le_bytes()))
+            .unwrap();
+        bridge.preprocess(&ctx).unwrap();
+        let message = |(opcode, payload): (u8, Vec<u8>)| {
+            assert_eq!(opcode, 0x2);
+            assert_eq!(payload[0], 0x01);
+            let subscription = u32::from_le_bytes(payload[1..5].try_into().unwrap());
+            let stamp = u64::from_le_bytes(payload[5..13].try_into().unwrap());
+            (subscription, stamp, payload[13..].to_vec())
+        };
+        let (subscription, stamp, json) = message(client.read());
+        assert_eq!((subscription, stamp), (7, 42));
+        let positions: PositionsSample = serde_json::from_slice(&json).unwrap();
+        assert_eq!(positions.seq, 1);
+        assert_eq!(positions.ids, [1]);
+        assert_eq!(positions.positions, [2048.0]);
+        let (subscription, _, json) = message(client.read());
+        assert_eq!(subscription, 8);
+        let diagnostics: DiagnosticsSample = serde_json::from_slice(&json).unwrap();
+        assert_eq!(diagnostics.servos.len(), 1);
+        assert_eq!(diagnostics.servos[0].id, 1);
+        assert!(diagnostics.servos[0].errors.is_empty());
+        assert_eq!(diagnostics.servos[0].load, None);
+
+        // Clients come and go: a close handshake and a dropped connection
+        // both remove the client.
+        let second = FoxgloveClient::connect(addr);
+        wait_until(|| server(&bridge).clients() == 2);
+        client.send(0x8, &[]);
+        assert_eq!(client.read().0, 0x8);
+        wait_until(|| server(&bridge).clients() == 1);
+        drop(second);
+        wait_until(|| server(&bridge).clients() == 0);
+        assert!(!server(&bridge).wants_positions());
     }
 }
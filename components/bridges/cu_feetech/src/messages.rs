@@ -8,7 +8,12 @@
 //! - `"rad"` — radians relative to calibration center (0 = center).
 //! - `"normalize"` — [-1, 1] over calibrated min..max (same scale for leader/follower).
 
+use bincode::de::Decoder;
+use bincode::error::DecodeError;
+use bincode::{Decode, Encode};
 use cu29::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::ops::{Deref, DerefMut};
 
 /// Maximum number of servos supported on a single bus.
 ///
@@ -19,5 +24,125 @@ pub const MAX_SERVOS: usize = 8;
 /// Joint positions for up to [`MAX_SERVOS`] Feetech bus servos.
 ///
 /// Values are `f32` so they can carry raw ticks, degrees, or radians
-/// depending on the bridge configuration.
-pub type JointPositions = CuArray<f32, MAX_SERVOS>;
+/// depending on the bridge configuration.  The message derefs to its
+/// [`values`](Self::values), so it reads like the plain array.
+///
+/// On the `positions` Rx channel `seq` is the bridge's cycle sequence
+/// number: it increases by one every cycle the bridge polls the bus
+/// (starting at 1), so a jump larger than one in consecutive messages means
+/// cycles were dropped or withheld.  It is independent of any
+/// transport-level sequence number.  `stamp_ns` is the time the positions
+/// were read, on the clock selected with `"stamp_clock"` (see
+/// [`stamp`](crate::stamp)).  The bridge ignores `seq` and `stamp_ns` on
+/// `goal_positions`, so a `positions` output can be wired straight to
+/// another bridge's goals.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Encode, Reflect)]
+pub struct JointPositions {
+    /// Bridge cycle sequence number, 0 when not set.
+    pub seq: u64,
+    /// Read time in nanoseconds on the configured stamp clock, 0 when unknown.
+    pub stamp_ns: u64,
+    /// One position per servo slot, in the configured unit.
+    pub values: CuArray<f32, MAX_SERVOS>,
+}
+
+impl JointPositions {
+    /// Empty positions with `seq` and `stamp_ns` 0.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Deref for JointPositions {
+    type Target = CuArray<f32, MAX_SERVOS>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.values
+    }
+}
+
+impl DerefMut for JointPositions {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.values
+    }
+}
+
+// `CuArray` only decodes without context, so the derive cannot be used here.
+impl Decode<()> for JointPositions {
+    fn decode<D: Decoder<Context = ()>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        Ok(Self {
+            seq: Decode::decode(decoder)?,
+            stamp_ns: Decode::decode(decoder)?,
+            values: Decode::decode(decoder)?,
+        })
+    }
+}
+
+/// Joint velocities for up to [`MAX_SERVOS`] Feetech bus servos.
+///
+/// Values are in the bridge's `"velocity_units"` per second: raw steps/s,
+/// degrees/s, radians/s, or normalized units/s.
+pub type JointVelocities = CuArray<f32, MAX_SERVOS>;
+
+/// Time spent in each phase of one bridge cycle.
+///
+/// Published on the `profile` Rx channel, one cycle late (see
+/// [`profile`](crate::profile)).  Durations are measured with the robot
+/// clock and only cover the bridge's own callbacks.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Encode, Decode, Reflect,
+)]
+pub struct CycleProfile {
+    /// Number of the profiled cycle, from 1.  Counted by the profiler, so
+    /// it advances every cycle, bus polled or not; while the bus is polled
+    /// every cycle it matches the `seq` of that cycle's positions.
+    pub seq: u64,
+    /// Writing instruction packets to the port.
+    pub write: CuDuration,
+    /// Waiting for and reading servo status packets.
+    pub wait: CuDuration,
+    /// Converting positions and goals between raw ticks and the configured units.
+    pub convert: CuDuration,
+    /// Setting payloads on Rx messages.
+    pub publish: CuDuration,
+    /// Everything else in the callbacks: bookkeeping, smoothing, logging.
+    pub other: CuDuration,
+    /// Time spent in the bridge's callbacks during the cycle.
+    pub total: CuDuration,
+}
+
+impl CycleProfile {
+    /// Sum of the phases, equal to `total` for a finished profile.
+    pub fn phases_sum(&self) -> CuDuration {
+        self.write + self.wait + self.convert + self.publish + self.other
+    }
+}
+
+/// ROS2 time (`builtin_interfaces/Time`), sent on the `ros_clock` Tx channel.
+///
+/// Only used with `"stamp_clock": "ros2"`; forward the `/clock` topic here.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Encode, Decode, Reflect,
+)]
+pub struct RosTime {
+    pub sec: i32,
+    pub nanosec: u32,
+}
+
+impl RosTime {
+    /// Nanoseconds since the ROS2 clock's epoch.
+    pub fn as_nanos(&self) -> i64 {
+        self.sec as i64 * 1_000_000_000 + self.nanosec as i64
+    }
+}
+
+/// Emergency stop command, sent on the `estop` Tx channel.
+///
+/// `engaged: true` cuts torque on every servo and latches; goals are ignored
+/// until a message with `engaged: false` releases it.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Encode, Decode, Reflect,
+)]
+pub struct EStop {
+    pub engaged: bool,
+}
@@ -0,0 +1,181 @@
+//! Per-phase timing of bridge cycles.
+//!
+//! With profiling enabled the bridge times, with the robot clock, every
+//! callback of a cycle (`preprocess`, `receive`, `send`, `postprocess`) and
+//! within them:
+//!
+//! - **write**: instruction packets written to the port,
+//! - **wait**: waiting for and reading servo status packets,
+//! - **convert**: unit conversion of positions and goals,
+//! - **publish**: setting payloads on Rx messages.
+//!
+//! Whatever else the bridge does (bookkeeping, smoothing, logging) is
+//! reported as **other**, so the phases always add up to the total.  Time
+//! spent by other tasks between the bridge's callbacks is not counted.
+//!
+//! Phases do not overlap: a phase timed inside another one (e.g. the bus read
+//! that seeds a goal smoother during conversion) counts only towards the
+//! inner phase, and is left out of the outer one.
+//!
+//! A cycle runs from one `preprocess` to the next, so the profile of a cycle
+//! is complete, and published on the `profile` channel, one cycle later.
+//! Cycles are numbered by the profiler itself, so the numbering also runs
+//! when the bus is not polled.
+
+use crate::messages::CycleProfile;
+use cu29::clock::{CuDuration, CuTime, RobotClock};
+
+/// Add the phases and total of `other` to `sum`.
+fn accumulate(sum: &mut CycleProfile, other: &CycleProfile) {
+    sum.write += other.write;
+    sum.wait += other.wait;
+    sum.convert += other.convert;
+    sum.publish += other.publish;
+    sum.other += other.other;
+    sum.total += other.total;
+}
+
+/// A timed phase of a cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Write,
+    Wait,
+    Convert,
+    Publish,
+}
+
+/// Start of a timed phase, from [`Profiler::start`].
+#[derive(Debug, Clone, Copy)]
+pub struct PhaseStart {
+    at: CuTime,
+    /// Time already attributed to phases when this one started.
+    attributed: CuDuration,
+}
+
+/// Accumulates [`CycleProfile`]s.
+#[derive(Debug, Clone)]
+pub struct Profiler {
+    /// Taken from the first callback's context.
+    clock: Option<RobotClock>,
+    /// Inside a bridge callback; phases outside one (e.g. during `start`)
+    /// are not counted.
+    inside: bool,
+    /// A callback ran since the last finished cycle.
+    active: bool,
+    current: CycleProfile,
+    /// Time attributed to phases in the current cycle, nested ones included.
+    attributed: CuDuration,
+    /// Cycles finished so far.
+    cycles: u64,
+    last: Option<CycleProfile>,
+    /// Log the mean profile every this many cycles; 0 disables the log.
+    log_every: u32,
+    sum: CycleProfile,
+    summed: u32,
+}
+
+impl Profiler {
+    pub fn new(log_every: u32) -> Self {
+        Self {
+            clock: None,
+            inside: false,
+            active: false,
+            current: CycleProfile::default(),
+            attributed: CuDuration::default(),
+            cycles: 0,
+            last: None,
+            log_every,
+            sum: CycleProfile::default(),
+            summed: 0,
+        }
+    }
+
+    /// Start timing a bridge callback.
+    pub fn enter(&mut self, clock: &RobotClock) -> CuTime {
+        self.inside = true;
+        self.active = true;
+        self.clock.get_or_insert_with(|| clock.clone()).now()
+    }
+
+    /// Robot time, or `None` before the first callback.
+    pub fn now(&self) -> Option<CuTime> {
+        self.clock.as_ref().map(RobotClock::now)
+    }
+
+    /// Start timing a phase, or `None` before the first callback.
+    pub fn start(&self) -> Option<PhaseStart> {
+        Some(PhaseStart {
+            at: self.now()?,
+            attributed: self.attributed,
+        })
+    }
+
+    /// Add the time since `started` to `phase`, minus the time spent in
+    /// phases nested inside it.
+    pub fn add(&mut self, phase: Phase, started: PhaseStart) {
+        let Some(now) = self.now().filter(|_| self.inside) else {
+            return;
+        };
+        let nested = self.attributed.0.saturating_sub(started.attributed.0);
+        let elapsed = CuDuration((now - started.at).0.saturating_sub(nested));
+        self.attributed += elapsed;
+        let slot = match phase {
+            Phase::Write => &mut self.current.write,
+            Phase::Wait => &mut self.current.wait,
+            Phase::Convert => &mut self.current.convert,
+            Phase::Publish => &mut self.current.publish,
+        };
+        *slot += elapsed;
+    }
+
+    /// Add the time since `entered` to the cycle total.
+    pub fn leave(&mut self, entered: CuTime) {
+        self.inside = false;
+        if let Some(now) = self.now() {
+            self.current.total += now - entered;
+        }
+    }
+
+    /// Close the current cycle and start a new one.
+    ///
+    /// Returns the mean profile when a log is due.  Does nothing if no
+    /// callback ran since the last call.
+    pub fn finish_cycle(&mut self) -> Option<CycleProfile> {
+        if !core::mem::take(&mut self.active) {
+            return None;
+        }
+        self.cycles += 1;
+        let seq = self.cycles;
+        self.attributed = CuDuration::default();
+        let mut done = core::mem::take(&mut self.current);
+        done.seq = seq;
+        let timed = done.write + done.wait + done.convert + done.publish;
+        done.other = CuDuration(done.total.0.saturating_sub(timed.0));
+        self.last = Some(done);
+        if self.log_every == 0 {
+            return None;
+        }
+        accumulate(&mut self.sum, &done);
+        self.summed += 1;
+        if self.summed < self.log_every {
+            return None;
+        }
+        let n = self.summed as u64;
+        let sum = core::mem::take(&mut self.sum);
+        self.summed = 0;
+        Some(CycleProfile {
+            seq,
+            write: sum.write / n,
+            wait: sum.wait / n,
+            convert: sum.convert / n,
+            publish: sum.publish / n,
+            other: sum.other / n,
+            total: sum.total / n,
+        })
+    }
+
+    /// Profile of the last finished cycle.
+    pub fn last(&self) -> Option<CycleProfile> {
+        self.last
+    }
+}
@@ -0,0 +1,129 @@
+//! Flight recorder for post-mortem debugging.
+//!
+//! The bridge keeps the raw positions and read failures of the last `depth`
+//! cycles in memory and appends them to a file when a fault occurs, so the
+//! context leading up to it is available without logging every cycle.
+//!
+//! Each dump starts with a `# fault: ...` comment line, followed by a CSV
+//! header and one row per cycle, oldest first:
+//!
+//! ```text
+//! # fault: e-stop engaged
+//! seq,time_ns,servo1,servo2
+//! 41,410000000,2048,1990
+//! 42,420000000,2049,ERR
+//! ```
+//!
+//! `ERR` marks a servo that did not answer that cycle.
+
+use crate::messages::MAX_SERVOS;
+use cu29::clock::CuTime;
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::Path;
+
+/// Default dump file, relative to the working directory.
+pub const DEFAULT_RECORDER_FILE: &str = "feetech_flight_recorder.csv";
+
+/// Consecutive failed reads after which a servo is considered dead.
+pub const DEAD_SERVO_READS: usize = 3;
+
+/// One cycle of bus state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecorderEntry {
+    /// Bridge cycle sequence number.
+    pub seq: u64,
+    /// Robot time of the read.
+    pub time: CuTime,
+    /// Raw position per servo slot (last known value if the read failed).
+    pub raw: [u16; MAX_SERVOS],
+    /// Whether each servo slot failed to answer.
+    pub failed: [bool; MAX_SERVOS],
+}
+
+/// Fixed-depth ring buffer of the most recent cycles.
+///
+/// Fault conditions are tracked next to the buffer and independently of its
+/// depth, so each fault is reported once when it starts, however many cycles
+/// are kept.
+#[derive(Debug, Clone)]
+pub struct FlightRecorder {
+    depth: usize,
+    entries: VecDeque<RecorderEntry>,
+    /// Consecutive failed reads per servo slot.
+    failures: [usize; MAX_SERVOS],
+    /// Whether each servo slot reported an overload on its last answer.
+    overloaded: [bool; MAX_SERVOS],
+}
+
+impl FlightRecorder {
+    pub fn new(depth: usize) -> Self {
+        let depth = depth.max(1);
+        Self {
+            depth,
+            entries: VecDeque::with_capacity(depth),
+            failures: [0; MAX_SERVOS],
+            overloaded: [false; MAX_SERVOS],
+        }
+    }
+
+    /// Record one cycle, dropping the oldest once `depth` entries are held.
+    pub fn record(&mut self, entry: RecorderEntry) {
+        for (count, failed) in self.failures.iter_mut().zip(entry.failed) {
+            *count = if failed { *count + 1 } else { 0 };
+        }
+        if self.entries.len() == self.depth {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// Recorded cycles, oldest first.
+    pub fn entries(&self) -> impl Iterator<Item = &RecorderEntry> {
+        self.entries.iter()
+    }
+
+    /// Number of most recent cycles in which servo slot `i` failed to answer.
+    ///
+    /// Not limited by the depth: cycles already dropped from the buffer
+    /// still count.
+    pub fn consecutive_failures(&self, i: usize) -> usize {
+        self.failures[i]
+    }
+
+    /// Record whether servo slot `i` reports an overload.
+    ///
+    /// Returns `true` when the overload is new since the last call.
+    pub fn observe_overload(&mut self, i: usize, overloaded: bool) -> bool {
+        let started = overloaded && !self.overloaded[i];
+        self.overloaded[i] = overloaded;
+        started
+    }
+
+    /// Append the recorded cycles for servos `ids` to `path`.
+    pub fn dump(&self, path: &Path, ids: &[u8], reason: &str) -> std::io::Result<()> {
+        let mut out = String::new();
+        out.push_str(&format!("# fault: {reason}\nseq,time_ns"));
+        for id in ids {
+            out.push_str(&format!(",servo{id}"));
+        }
+        out.push('\n');
+        for e in &self.entries {
+            out.push_str(&format!("{},{}", e.seq, e.time.as_nanos()));
+            for i in 0..ids.len() {
+                if e.failed[i] {
+                    out.push_str(",ERR");
+                } else {
+                    out.push_str(&format!(",{}", e.raw[i]));
+                }
+            }
+            out.push('\n');
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        file.write_all(out.as_bytes())?;
+        file.flush()
+    }
+}
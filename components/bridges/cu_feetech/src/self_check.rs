@@ -0,0 +1,109 @@
+//! Jog self-check for bring-up.
+//!
+//! Each servo is jogged a small amount forward and then backward from where
+//! it sits, and returned there.  Targets are clamped to the position range;
+//! a servo too close to the top of it is jogged backward first, so both jogs
+//! still cover at least the jog amplitude.  After each jog the present position must
+//! have moved in the commanded direction by the commanded amount, within a
+//! tolerance.  This exercises both the motor and the position feedback:
+//! a dead motor or a frozen encoder shows up as no response, a swapped
+//! direction or a bad gear ratio as a wrong direction or magnitude.
+
+use cu29::clock::CuDuration;
+use serde::Serialize;
+
+/// Default jog amplitude, in raw ticks.
+pub const DEFAULT_JOG_TICKS: u16 = 100;
+
+/// Default allowed error on the jog, in raw ticks.
+pub const DEFAULT_JOG_TOLERANCE: u16 = 30;
+
+/// Default speed limit while jogging, in `GOAL_SPEED` units (steps/s).
+pub const DEFAULT_JOG_SPEED: u16 = 200;
+
+/// Default time allowed for each jog to settle.
+pub const DEFAULT_JOG_SETTLE_MS: u64 = 1_000;
+
+/// Time between two position reads while waiting for a jog to settle.
+pub const JOG_POLL_INTERVAL_MS: u64 = 10;
+
+/// Self-check parameters, shared by all servos.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JogConfig {
+    /// How far to jog in each direction.
+    pub ticks: u16,
+    /// Allowed difference between the commanded and the measured jog.
+    pub tolerance: u16,
+    /// Speed limit while jogging.
+    pub speed: u16,
+    /// Time allowed for each jog to reach its target.
+    pub settle: CuDuration,
+}
+
+impl Default for JogConfig {
+    fn default() -> Self {
+        Self {
+            ticks: DEFAULT_JOG_TICKS,
+            tolerance: DEFAULT_JOG_TOLERANCE,
+            speed: DEFAULT_JOG_SPEED,
+            settle: CuDuration::from_millis(DEFAULT_JOG_SETTLE_MS),
+        }
+    }
+}
+
+/// Outcome of one jog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum JogVerdict {
+    /// Moved in the commanded direction by the commanded amount.
+    Pass,
+    /// The present position could not be read.
+    NoFeedback,
+    /// Moved less than the tolerance.
+    NoResponse,
+    /// Moved the other way.
+    WrongDirection,
+    /// Moved the right way, but too little or too much.
+    WrongMagnitude,
+}
+
+/// Self-check report for one servo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JogResult {
+    /// Bus ID of the servo.
+    pub id: u8,
+    /// Raw position the servo started (and was returned to).
+    pub start: u16,
+    /// Verdict of the jog toward increasing ticks.
+    pub forward: JogVerdict,
+    /// Verdict of the jog toward decreasing ticks.
+    pub backward: JogVerdict,
+}
+
+impl JogResult {
+    /// `true` when both jogs passed.
+    pub fn passed(&self) -> bool {
+        self.forward == JogVerdict::Pass && self.backward == JogVerdict::Pass
+    }
+}
+
+/// Judge one jog from `from` toward `target` that ended at `reached`.
+///
+/// The expected motion is `target - from`, so a jog clamped at the end of
+/// the register range is judged against what was actually commanded.
+pub fn evaluate_jog(from: u16, target: u16, reached: Option<u16>, tolerance: u16) -> JogVerdict {
+    let Some(reached) = reached else {
+        return JogVerdict::NoFeedback;
+    };
+    let expected = target as i32 - from as i32;
+    let moved = reached as i32 - from as i32;
+    let tolerance = tolerance as i32;
+    if moved.abs() <= tolerance {
+        JogVerdict::NoResponse
+    } else if moved.signum() != expected.signum() {
+        JogVerdict::WrongDirection
+    } else if (moved - expected).abs() > tolerance {
+        JogVerdict::WrongMagnitude
+    } else {
+        JogVerdict::Pass
+    }
+}
@@ -0,0 +1,88 @@
+//! Per-joint smoothing of goal positions.
+//!
+//! Each joint has its own filter so latency-critical joints (e.g. a wrist)
+//! can bypass smoothing while noisy ones are filtered.  Two stages run in
+//! order, both in the goal (input) unit:
+//!
+//! 1. **Low-pass**: exponential moving average with factor `alpha` in
+//!    `(0, 1]`.  `1.0` passes the goal through unchanged.
+//! 2. **Slew limit**: the goal moves at most `max_step` per cycle.  `0.0`
+//!    disables the limit.
+//!
+//! Before the first goal after start (or after [`JointSmoother::reset`]) the
+//! bridge [seeds](JointSmoother::seed) the filter with the joint's present
+//! position, so that goal is already filtered and slew-limited from where the
+//! joint actually is.  An unseeded filter passes its first goal through.
+
+/// Smoothing parameters and state for one joint.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JointSmoother {
+    /// EMA factor: weight of the new goal, in `(0, 1]`.
+    pub alpha: f32,
+    /// Maximum change per cycle; `0.0` means unlimited.
+    pub max_step: f32,
+    /// Last smoothed goal, if any.
+    pub last: Option<f32>,
+}
+
+impl Default for JointSmoother {
+    fn default() -> Self {
+        Self::bypass()
+    }
+}
+
+impl JointSmoother {
+    pub fn new(alpha: f32, max_step: f32) -> Self {
+        Self {
+            alpha: alpha.clamp(f32::MIN_POSITIVE, 1.0),
+            max_step: max_step.max(0.0),
+            last: None,
+        }
+    }
+
+    /// A smoother that leaves goals untouched.
+    pub fn bypass() -> Self {
+        Self::new(1.0, 0.0)
+    }
+
+    /// `true` when this joint is not smoothed at all.
+    pub fn is_bypass(&self) -> bool {
+        self.alpha >= 1.0 && self.max_step == 0.0
+    }
+
+    /// `true` when the filter has no state yet and should be seeded before
+    /// the next goal.
+    pub fn needs_seed(&self) -> bool {
+        !self.is_bypass() && self.last.is_none()
+    }
+
+    /// Start the filter from `present`, the joint's present position.
+    pub fn seed(&mut self, present: f32) {
+        self.last = Some(present);
+    }
+
+    /// Smooth one goal and return the value to command.
+    pub fn apply(&mut self, target: f32) -> f32 {
+        if self.is_bypass() {
+            return target;
+        }
+        let out = match self.last {
+            None => target,
+            Some(last) => {
+                let filtered = last + self.alpha * (target - last);
+                if self.max_step > 0.0 {
+                    last + (filtered - last).clamp(-self.max_step, self.max_step)
+                } else {
+                    filtered
+                }
+            }
+        };
+        self.last = Some(out);
+        out
+    }
+
+    /// Forget the filter state; it needs a new seed.
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+}
@@ -0,0 +1,90 @@
+//! Clock source for the `stamp_ns` on published positions.
+//!
+//! The `tov` of every Rx message is robot time, which is monotonic and starts
+//! near zero, so it cannot be correlated with timestamps from ROS2 tools.
+//! `stamp_ns` carries the same read instant on a clock selected with
+//! `"stamp_clock"`:
+//!
+//! - `"robot"` (default): the robot clock, identical to the `tov`.
+//! - `"wall"`: the system wall clock, in nanoseconds since the Unix epoch.
+//! - `"ros2"`: the ROS2 `/clock`, fed to the bridge on the `ros_clock` Tx
+//!   channel (e.g. forwarded by a ROS2 subscriber task).  Each message sets
+//!   the offset between `/clock` and the robot clock; reads in between are
+//!   stamped by extrapolating from the latest one with the robot clock.
+//!   Until the first `/clock` message arrives positions are stamped 0.
+
+use crate::messages::RosTime;
+use cu29::prelude::*;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Clock the positions' `stamp_ns` is taken from (`"stamp_clock"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StampClock {
+    /// Robot clock, same as the message `tov`.
+    #[default]
+    Robot,
+    /// System wall clock, nanoseconds since the Unix epoch.
+    Wall,
+    /// ROS2 `/clock`, received on the `ros_clock` Tx channel.
+    Ros2,
+}
+
+impl core::str::FromStr for StampClock {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "robot" => Ok(Self::Robot),
+            "wall" => Ok(Self::Wall),
+            "ros2" => Ok(Self::Ros2),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Stamps read instants on the configured [`StampClock`].
+#[derive(Debug, Clone, Default)]
+pub struct StampSource {
+    clock: StampClock,
+    /// `/clock` minus robot time at the latest `/clock` message, in ns.
+    ros_offset: Option<i128>,
+}
+
+impl StampSource {
+    pub fn new(clock: StampClock) -> Self {
+        Self {
+            clock,
+            ros_offset: None,
+        }
+    }
+
+    pub fn clock(&self) -> StampClock {
+        self.clock
+    }
+
+    /// `true` once a `/clock` message has been seen.
+    pub fn has_ros_time(&self) -> bool {
+        self.ros_offset.is_some()
+    }
+
+    /// Record a `/clock` message received at robot time `now`.
+    pub fn observe_ros_clock(&mut self, time: &RosTime, now: CuTime) {
+        self.ros_offset = Some(time.as_nanos() as i128 - now.as_nanos() as i128);
+    }
+
+    /// Stamp for an instant read at robot time `read_at`, in nanoseconds.
+    ///
+    /// The wall clock is sampled when this is called, so call it right
+    /// after the read.  Returns 0 on `"ros2"` before any `/clock` message.
+    pub fn stamp(&self, read_at: CuTime) -> u64 {
+        match self.clock {
+            StampClock::Robot => read_at.as_nanos(),
+            StampClock::Wall => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |since| since.as_nanos() as u64),
+            StampClock::Ros2 => self.ros_offset.map_or(0, |offset| {
+                (read_at.as_nanos() as i128 + offset).clamp(0, u64::MAX as i128) as u64
+            }),
+        }
+    }
+}
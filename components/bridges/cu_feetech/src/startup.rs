@@ -0,0 +1,202 @@
+//! Ordered startup sequence.
+//!
+//! [`start`](cu29::cubridge::CuBridge::start) runs a list of [`StartupStep`]s
+//! in order and stops at the first one that fails.  The default order is
+//!
+//! 1. `check_home`: compare present positions with the expected homes,
+//! 2. `homing`: drive servos configured for it against their hard stop,
+//! 3. `self_check`: jog each servo (only if `"self_check_on_start"` is set),
+//! 4. `torque`: enable torque when goal writers are connected.
+//!
+//! `"startup_sequence"` replaces it with an explicit list of step names.
+//! `ping` (check that every servo answers) is available but not in the
+//! default.  Application code can add its own steps by handing the bridge a
+//! [`StartupHooks`] set through its optional `startup_hooks` resource and
+//! listing them as `"hook:<name>"`.
+//!
+//! An explicit sequence must still cover what the configuration asks for:
+//! leaving out `homing` while a servo has `"homing<i>"`, or `check_home`
+//! while a `"home<i>"` is set, is an error, and so is combining it with
+//! `"self_check_on_start"` (list `self_check` instead).  Leaving out
+//! `torque` while goal writers are connected is only warned about, since a
+//! hook may enable torque itself.
+
+use crate::FeetechBridge;
+use cu29::prelude::*;
+use std::collections::BTreeMap;
+
+/// A user step in the startup sequence.
+pub type StartupHook = fn(&mut FeetechBridge, &CuContext) -> CuResult<()>;
+
+/// User steps available as `"hook:<name>"` in `"startup_sequence"`.
+///
+/// Export one from a resource bundle and bind it to the bridge's
+/// `startup_hooks` resource; hook names are resolved when the bridge is
+/// constructed.
+#[derive(Debug, Clone, Default)]
+pub struct StartupHooks(BTreeMap<String, StartupHook>);
+
+impl StartupHooks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make `hook` available as `"hook:<name>"`.
+    ///
+    /// Registering a name again replaces the previous hook.
+    pub fn register(&mut self, name: &str, hook: StartupHook) {
+        self.0.insert(name.to_string(), hook);
+    }
+
+    fn get(&self, name: &str) -> Option<StartupHook> {
+        self.0.get(name).copied()
+    }
+}
+
+/// One step of the startup sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartupStep {
+    /// Ping every servo; fails if one does not answer.
+    Ping,
+    /// Compare present positions with the expected homes.
+    CheckHome,
+    /// Home servos against their hard stops.
+    Homing,
+    /// Run the jog self-check.
+    SelfCheck,
+    /// Enable torque if goal writers are connected.
+    Torque,
+    /// A step registered in the bridge's [`StartupHooks`].
+    Hook(String),
+}
+
+impl core::str::FromStr for StartupStep {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ping" => Ok(Self::Ping),
+            "check_home" => Ok(Self::CheckHome),
+            "homing" => Ok(Self::Homing),
+            "self_check" => Ok(Self::SelfCheck),
+            "torque" => Ok(Self::Torque),
+            _ => match s.strip_prefix("hook:") {
+                Some(name) if !name.is_empty() => Ok(Self::Hook(name.to_string())),
+                _ => Err(()),
+            },
+        }
+    }
+}
+
+impl core::fmt::Display for StartupStep {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Ping => f.write_str("ping"),
+            Self::CheckHome => f.write_str("check_home"),
+            Self::Homing => f.write_str("homing"),
+            Self::SelfCheck => f.write_str("self_check"),
+            Self::Torque => f.write_str("torque"),
+            Self::Hook(name) => write!(f, "hook:{name}"),
+        }
+    }
+}
+
+/// Where the startup sequence is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StartupState {
+    /// Not started yet.
+    #[default]
+    Pending,
+    /// Step `n` (index into the sequence) is running.
+    Running(usize),
+    /// Every step succeeded.
+    Done,
+    /// Step `n` failed; the steps after it were not run.
+    Failed(usize),
+}
+
+/// The steps to run on start, in order, and how far they got.
+#[derive(Debug, Clone)]
+pub struct StartupSequence {
+    steps: Vec<(StartupStep, Option<StartupHook>)>,
+    state: StartupState,
+}
+
+impl StartupSequence {
+    /// The default order; `self_check` is included only if requested.
+    pub fn default_order(self_check: bool) -> Self {
+        let mut steps = vec![StartupStep::CheckHome, StartupStep::Homing];
+        if self_check {
+            steps.push(StartupStep::SelfCheck);
+        }
+        steps.push(StartupStep::Torque);
+        Self::new(steps, &StartupHooks::default()).expect("default startup sequence is valid")
+    }
+
+    /// Build a sequence, resolving hooks from `hooks`.
+    ///
+    /// Fails on a hook that is not registered or a step listed twice.
+    pub fn new(steps: Vec<StartupStep>, hooks: &StartupHooks) -> Result<Self, String> {
+        let mut resolved = Vec::with_capacity(steps.len());
+        for (n, step) in steps.iter().enumerate() {
+            if steps[..n].contains(step) {
+                return Err(format!("step \"{step}\" listed twice"));
+            }
+            let hook = match step {
+                StartupStep::Hook(name) => Some(
+                    hooks
+                        .get(name)
+                        .ok_or_else(|| format!("no hook registered as \"{name}\""))?,
+                ),
+                _ => None,
+            };
+            resolved.push((step.clone(), hook));
+        }
+        Ok(Self {
+            steps: resolved,
+            state: StartupState::Pending,
+        })
+    }
+
+    /// The steps, in order.
+    pub fn steps(&self) -> impl Iterator<Item = &StartupStep> {
+        self.steps.iter().map(|(step, _)| step)
+    }
+
+    /// How far the last run of the sequence got.
+    pub fn state(&self) -> StartupState {
+        self.state
+    }
+
+    /// Advance to the next step and return it with its hook, or `None` once
+    /// the sequence is done or has failed.
+    pub fn advance(&mut self) -> Option<(StartupStep, Option<StartupHook>)> {
+        let next = match self.state {
+            StartupState::Pending => 0,
+            StartupState::Running(n) => n + 1,
+            StartupState::Done | StartupState::Failed(_) => return None,
+        };
+        match self.steps.get(next) {
+            Some(step) => {
+                self.state = StartupState::Running(next);
+                Some(step.clone())
+            }
+            None => {
+                self.state = StartupState::Done;
+                None
+            }
+        }
+    }
+
+    /// Mark the running step as failed.
+    pub fn fail(&mut self) {
+        if let StartupState::Running(n) = self.state {
+            self.state = StartupState::Failed(n);
+        }
+    }
+
+    /// Go back to [`StartupState::Pending`], for a restart.
+    pub fn reset(&mut self) {
+        self.state = StartupState::Pending;
+    }
+}